@@ -0,0 +1,54 @@
+//! Exposes the version of `apollo-federation` this crate is built with as the
+//! `APOLLO_FEDERATION_VERSION` environment variable, so that reproduction bundles can record it.
+//!
+//! `apollo-federation` is pinned to an exact version, so its version requirement is the version
+//! that gets built.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use toml::{Table, Value};
+
+fn main() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let manifest_path = manifest_dir.join("Cargo.toml");
+    println!("cargo:rerun-if-changed={}", manifest_path.display());
+    let manifest = read_manifest(&manifest_path);
+    let mut dependency = manifest["dependencies"]["apollo-federation"].clone();
+
+    // Published manifests have workspace dependencies inlined, so this is only needed in the
+    // workspace
+    if dependency.get("workspace").and_then(Value::as_bool) == Some(true) {
+        let workspace_path = manifest_dir
+            .ancestors()
+            .skip(1)
+            .map(|dir| dir.join("Cargo.toml"))
+            .find(|path| path.exists() && read_manifest(path).contains_key("workspace"))
+            .expect("the workspace manifest should be in a parent directory");
+        println!("cargo:rerun-if-changed={}", workspace_path.display());
+        dependency = read_manifest(&workspace_path)["workspace"]["dependencies"]
+            ["apollo-federation"]
+            .clone();
+    }
+
+    let requirement = match &dependency {
+        Value::String(requirement) => requirement.as_str(),
+        Value::Table(table) => table["version"].as_str().unwrap_or_default(),
+        _ => "",
+    };
+    let Some(version) = requirement.strip_prefix('=') else {
+        panic!("apollo-federation must be pinned to an exact version, not \"{requirement}\"");
+    };
+    println!(
+        "cargo:rustc-env=APOLLO_FEDERATION_VERSION={}",
+        version.trim()
+    );
+}
+
+fn read_manifest(path: &Path) -> Table {
+    fs::read_to_string(path)
+        .unwrap_or_else(|err| panic!("could not read {}: {err}", path.display()))
+        .parse()
+        .unwrap_or_else(|err| panic!("could not parse {}: {err}", path.display()))
+}
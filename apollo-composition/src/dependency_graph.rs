@@ -0,0 +1,478 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+
+use apollo_compiler::executable::{FieldSet, Selection};
+use apollo_compiler::schema::{Directive, ExtendedType};
+use apollo_compiler::validation::Valid;
+use apollo_compiler::{Name, Schema};
+use apollo_federation::composition::Supergraph;
+use apollo_federation_types::composition::Issue;
+use serde::{Deserialize, Serialize};
+
+/// The subgraphs of a supergraph, connected by the ways the query planner can jump between them.
+///
+/// Built from the `join__*` directives of a composed supergraph with
+/// [`SubgraphDependencyGraph::from_supergraph_sdl`], and rendered with
+/// [`SubgraphDependencyGraph::to_dot`] or [`SubgraphDependencyGraph::to_mermaid`].
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubgraphDependencyGraph {
+    /// Every subgraph name, sorted.
+    pub subgraphs: Vec<String>,
+
+    /// Sorted by source subgraph, then target subgraph, then kind.
+    pub edges: Vec<DependencyEdge>,
+}
+
+/// All the dependencies of one kind from one subgraph to another.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: DependencyKind,
+
+    /// The entity names (for [`DependencyKind::Key`]) or field coordinates (for
+    /// [`DependencyKind::Requires`] and [`DependencyKind::Provides`]) behind this edge, sorted.
+    pub labels: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DependencyKind {
+    /// `from` can fetch an entity from `to` using one of the `@key`s that `to` resolves.
+    Key,
+    /// A field in `from` has a `@requires` on fields resolved by `to`.
+    Requires,
+    /// A field in `from` has a `@provides` on fields otherwise resolved by `to`.
+    Provides,
+}
+
+impl DependencyKind {
+    fn label(self) -> &'static str {
+        match self {
+            DependencyKind::Key => "key",
+            DependencyKind::Requires => "requires",
+            DependencyKind::Provides => "provides",
+        }
+    }
+}
+
+impl SubgraphDependencyGraph {
+    /// Builds the graph from a composed supergraph.
+    pub fn from_supergraph_sdl(supergraph_sdl: &str) -> Result<Self, Issue> {
+        let supergraph = Supergraph::parse(supergraph_sdl)?;
+        Ok(Self::from_schema(supergraph.schema().schema()))
+    }
+
+    fn from_schema(schema: &Valid<Schema>) -> Self {
+        let graphs = subgraph_names(schema);
+        let mut edges: BTreeMap<(&str, &str, DependencyKind), BTreeSet<String>> = BTreeMap::new();
+
+        for (type_name, ty) in &schema.types {
+            let join_types: Vec<_> = ty
+                .directives()
+                .get_all("join__type")
+                .filter_map(|directive| Some((graph_of(directive, &graphs)?, directive)))
+                .collect();
+
+            // entity jumps
+            let resolvable_key_graphs: BTreeSet<&str> = join_types
+                .iter()
+                .filter(|(_, directive)| {
+                    directive.specified_argument_by_name("key").is_some()
+                        && bool_argument(directive, "resolvable").unwrap_or(true)
+                })
+                .map(|(graph, _)| *graph)
+                .collect();
+            for (from, _) in &join_types {
+                for to in &resolvable_key_graphs {
+                    if from != to {
+                        edges
+                            .entry((from, to, DependencyKind::Key))
+                            .or_default()
+                            .insert(type_name.to_string());
+                    }
+                }
+            }
+
+            // @requires and @provides
+            for (field_name, join_fields) in fields_with_join_fields(ty) {
+                for directive in join_fields {
+                    let Some(from) = graph_of(directive, &graphs) else {
+                        continue;
+                    };
+                    let coordinate = format!("{type_name}.{field_name}");
+                    if let Some(requires) = string_argument(directive, "requires") {
+                        for to in field_set_owners(schema, &graphs, type_name, requires) {
+                            if to != from {
+                                edges
+                                    .entry((from, to, DependencyKind::Requires))
+                                    .or_default()
+                                    .insert(coordinate.clone());
+                            }
+                        }
+                    }
+                    if let Some(provides) = string_argument(directive, "provides") {
+                        let Some(return_type) = field_type(ty, &field_name) else {
+                            continue;
+                        };
+                        for to in field_set_owners(schema, &graphs, return_type, provides) {
+                            if to != from {
+                                edges
+                                    .entry((from, to, DependencyKind::Provides))
+                                    .or_default()
+                                    .insert(coordinate.clone());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Self {
+            subgraphs: graphs.values().map(|name| name.to_string()).collect(),
+            edges: edges
+                .into_iter()
+                .map(|((from, to, kind), labels)| DependencyEdge {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    kind,
+                    labels: labels.into_iter().collect(),
+                })
+                .collect(),
+        }
+        .sorted()
+    }
+
+    fn sorted(mut self) -> Self {
+        self.subgraphs.sort();
+        self.edges
+            .sort_by(|a, b| (&a.from, &a.to, a.kind).cmp(&(&b.from, &b.to, b.kind)));
+        self
+    }
+
+    /// Renders the graph in the Graphviz DOT language. Key edges are solid, `@requires` edges are
+    /// dashed and `@provides` edges are dotted.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph subgraphs {\n");
+        for subgraph in &self.subgraphs {
+            let _ = writeln!(dot, "  \"{}\";", escape(subgraph));
+        }
+        for edge in &self.edges {
+            let style = match edge.kind {
+                DependencyKind::Key => "solid",
+                DependencyKind::Requires => "dashed",
+                DependencyKind::Provides => "dotted",
+            };
+            let _ = writeln!(
+                dot,
+                "  \"{}\" -> \"{}\" [label=\"{}: {}\", style={style}];",
+                escape(&edge.from),
+                escape(&edge.to),
+                edge.kind.label(),
+                escape(&edge.labels.join(", ")),
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Renders the graph as a Mermaid flowchart. Key edges are solid, `@requires` edges are dotted
+    /// and `@provides` edges are thick.
+    pub fn to_mermaid(&self) -> String {
+        // Subgraph names aren't necessarily valid Mermaid ids, so nodes are numbered instead
+        let ids: HashMap<&str, usize> = self
+            .subgraphs
+            .iter()
+            .enumerate()
+            .map(|(index, name)| (name.as_str(), index))
+            .collect();
+        let mut mermaid = String::from("flowchart LR\n");
+        for (index, subgraph) in self.subgraphs.iter().enumerate() {
+            let _ = writeln!(mermaid, "  s{index}[\"{}\"]", escape_mermaid(subgraph));
+        }
+        for edge in &self.edges {
+            let (Some(from), Some(to)) = (ids.get(edge.from.as_str()), ids.get(edge.to.as_str()))
+            else {
+                continue;
+            };
+            let arrow = match edge.kind {
+                DependencyKind::Key => "-->",
+                DependencyKind::Requires => "-.->",
+                DependencyKind::Provides => "==>",
+            };
+            let _ = writeln!(
+                mermaid,
+                "  s{from} {arrow}|\"{}: {}\"| s{to}",
+                edge.kind.label(),
+                escape_mermaid(&edge.labels.join(", ")),
+            );
+        }
+        mermaid
+    }
+}
+
+/// Maps each `join__Graph` enum value to the name of its subgraph.
+fn subgraph_names(schema: &Schema) -> BTreeMap<Name, &str> {
+    let Some(ExtendedType::Enum(join_graph)) = schema.types.get("join__Graph") else {
+        return BTreeMap::new();
+    };
+    join_graph
+        .values
+        .iter()
+        .filter_map(|(value, definition)| {
+            let name = definition
+                .directives
+                .get("join__graph")
+                .and_then(|directive| string_argument(directive, "name"))?;
+            Some((value.clone(), name))
+        })
+        .collect()
+}
+
+fn graph_of<'a>(directive: &Directive, graphs: &BTreeMap<Name, &'a str>) -> Option<&'a str> {
+    let value = directive.specified_argument_by_name("graph")?.as_enum()?;
+    graphs.get(value).copied()
+}
+
+fn string_argument<'a>(directive: &'a Directive, name: &str) -> Option<&'a str> {
+    directive.specified_argument_by_name(name)?.as_str()
+}
+
+fn bool_argument(directive: &Directive, name: &str) -> Option<bool> {
+    directive.specified_argument_by_name(name)?.to_bool()
+}
+
+fn fields_with_join_fields(ty: &ExtendedType) -> Vec<(Name, Vec<&Directive>)> {
+    macro_rules! join_fields {
+        ($node:ident) => {
+            $node
+                .fields
+                .iter()
+                .map(|(name, field)| {
+                    (
+                        name.clone(),
+                        field
+                            .directives
+                            .get_all("join__field")
+                            .map(|directive| &**directive)
+                            .collect(),
+                    )
+                })
+                .collect()
+        };
+    }
+    match ty {
+        ExtendedType::Object(node) => join_fields!(node),
+        ExtendedType::Interface(node) => join_fields!(node),
+        _ => Vec::new(),
+    }
+}
+
+fn field_type<'a>(ty: &'a ExtendedType, field_name: &Name) -> Option<&'a Name> {
+    match ty {
+        ExtendedType::Object(node) => Some(node.fields.get(field_name)?.ty.inner_named_type()),
+        ExtendedType::Interface(node) => Some(node.fields.get(field_name)?.ty.inner_named_type()),
+        _ => None,
+    }
+}
+
+/// The subgraphs which resolve (without `@external`) the top-level fields of a field set.
+fn field_set_owners<'a>(
+    schema: &Valid<Schema>,
+    graphs: &BTreeMap<Name, &'a str>,
+    type_name: &Name,
+    field_set: &str,
+) -> BTreeSet<&'a str> {
+    let field_set = match FieldSet::parse(schema, type_name.clone(), field_set, "field_set.graphql")
+    {
+        Ok(field_set) => field_set,
+        Err(with_errors) => with_errors.partial,
+    };
+    let Some(ty) = schema.types.get(type_name) else {
+        return BTreeSet::new();
+    };
+    let type_graphs: Vec<&str> = ty
+        .directives()
+        .get_all("join__type")
+        .filter_map(|directive| graph_of(directive, graphs))
+        .collect();
+    let fields = fields_with_join_fields(ty);
+
+    let mut owners = BTreeSet::new();
+    for selection in &field_set.selection_set.selections {
+        let Selection::Field(selected) = selection else {
+            continue;
+        };
+        let Some((_, join_fields)) = fields.iter().find(|(name, _)| *name == selected.name) else {
+            continue;
+        };
+        if join_fields.is_empty() {
+            owners.extend(type_graphs.iter().copied());
+        } else {
+            owners.extend(
+                join_fields
+                    .iter()
+                    .filter(|directive| !bool_argument(directive, "external").unwrap_or(false))
+                    .filter_map(|directive| graph_of(directive, graphs)),
+            );
+        }
+    }
+    owners
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(value: &str) -> String {
+    value.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use apollo_federation::composition::compose;
+    use apollo_federation::subgraph::typestate::Subgraph;
+
+    use super::*;
+
+    fn supergraph_sdl() -> String {
+        let subgraphs = vec![
+            Subgraph::parse(
+                "products",
+                "http://products",
+                r#"
+                extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@shareable"])
+                type Query { products: [Product] }
+                type Product @key(fields: "id") { id: ID! name: String @shareable weight: Int }
+                "#,
+            )
+            .unwrap(),
+            Subgraph::parse(
+                "shipping",
+                "http://shipping",
+                r#"
+                extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@external", "@requires"])
+                type Product @key(fields: "id") { id: ID! weight: Int @external estimate: Int @requires(fields: "weight") }
+                "#,
+            )
+            .unwrap(),
+            Subgraph::parse(
+                "reviews",
+                "http://reviews",
+                r#"
+                extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@external", "@provides"])
+                type Query { topReviews: [Review] }
+                type Review { body: String product: Product @provides(fields: "name") }
+                type Product @key(fields: "id", resolvable: false) { id: ID! name: String @external }
+                "#,
+            )
+            .unwrap(),
+        ];
+        compose(subgraphs, Default::default())
+            .unwrap()
+            .schema()
+            .schema()
+            .to_string()
+    }
+
+    #[test]
+    fn it_finds_dependencies_between_subgraphs() {
+        let graph = SubgraphDependencyGraph::from_supergraph_sdl(&supergraph_sdl()).unwrap();
+        assert_eq!(graph.subgraphs, vec!["products", "reviews", "shipping"]);
+        let edges: Vec<_> = graph
+            .edges
+            .iter()
+            .map(|edge| {
+                (
+                    edge.from.as_str(),
+                    edge.to.as_str(),
+                    edge.kind,
+                    edge.labels.join(","),
+                )
+            })
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                (
+                    "products",
+                    "shipping",
+                    DependencyKind::Key,
+                    "Product".to_string()
+                ),
+                (
+                    "reviews",
+                    "products",
+                    DependencyKind::Key,
+                    "Product".to_string()
+                ),
+                (
+                    "reviews",
+                    "products",
+                    DependencyKind::Provides,
+                    "Review.product".to_string()
+                ),
+                (
+                    "reviews",
+                    "shipping",
+                    DependencyKind::Key,
+                    "Product".to_string()
+                ),
+                (
+                    "shipping",
+                    "products",
+                    DependencyKind::Key,
+                    "Product".to_string()
+                ),
+                (
+                    "shipping",
+                    "products",
+                    DependencyKind::Requires,
+                    "Product.estimate".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_renders_dot_and_mermaid() {
+        let graph = SubgraphDependencyGraph {
+            subgraphs: vec!["products".to_string(), "shipping".to_string()],
+            edges: vec![
+                DependencyEdge {
+                    from: "shipping".to_string(),
+                    to: "products".to_string(),
+                    kind: DependencyKind::Key,
+                    labels: vec!["Product".to_string()],
+                },
+                DependencyEdge {
+                    from: "shipping".to_string(),
+                    to: "products".to_string(),
+                    kind: DependencyKind::Requires,
+                    labels: vec!["Product.estimate".to_string()],
+                },
+            ],
+        };
+        assert_eq!(
+            graph.to_dot(),
+            r#"digraph subgraphs {
+  "products";
+  "shipping";
+  "shipping" -> "products" [label="key: Product", style=solid];
+  "shipping" -> "products" [label="requires: Product.estimate", style=dashed];
+}
+"#
+        );
+        assert_eq!(
+            graph.to_mermaid(),
+            r#"flowchart LR
+  s0["products"]
+  s1["shipping"]
+  s1 -->|"key: Product"| s0
+  s1 -.->|"requires: Product.estimate"| s0
+"#
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use apollo_federation_types::build_plugin::{BuildMessage, PluginResult};
+use apollo_federation_types::composition::Issue;
+use apollo_federation_types::javascript::SubgraphDefinition;
+
+use crate::{HybridComposition, SupergraphSdl};
+
+/// A boxed, non-`Send` future, as returned by the methods of [`DynHybridComposition`].
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
+
+/// An object-safe version of [`HybridComposition`], so that implementations can be chosen at
+/// runtime (for example, JavaScript-backed vs. native composition) and used as
+/// `Box<dyn DynHybridComposition>`.
+///
+/// You shouldn't need to implement this trait yourself: every [`HybridComposition`] gets it
+/// through a blanket implementation, which forwards each method to its [`HybridComposition`]
+/// counterpart.
+pub trait DynHybridComposition {
+    /// See [`HybridComposition::compose_services_without_satisfiability`].
+    fn compose_services_without_satisfiability(
+        &mut self,
+        subgraph_definitions: Vec<SubgraphDefinition>,
+    ) -> BoxFuture<'_, Option<SupergraphSdl<'_>>>;
+
+    /// See [`HybridComposition::validate_satisfiability`].
+    fn validate_satisfiability(&mut self) -> BoxFuture<'_, Result<Vec<Issue>, Vec<Issue>>>;
+
+    /// See [`HybridComposition::update_supergraph_sdl`].
+    fn update_supergraph_sdl(&mut self, supergraph_sdl: String);
+
+    /// See [`HybridComposition::add_issues`].
+    fn add_issues(&mut self, issues: &mut dyn Iterator<Item = Issue>);
+
+    /// See [`HybridComposition::compose`].
+    fn compose(&mut self, subgraph_definitions: Vec<SubgraphDefinition>) -> BoxFuture<'_, ()>;
+
+    /// See [`HybridComposition::experimental_compose`].
+    fn experimental_compose<'a>(
+        self: Box<Self>,
+        subgraph_definitions: Vec<SubgraphDefinition>,
+    ) -> BoxFuture<'a, Result<PluginResult, Vec<BuildMessage>>>
+    where
+        Self: 'a;
+}
+
+impl<T: HybridComposition> DynHybridComposition for T {
+    fn compose_services_without_satisfiability(
+        &mut self,
+        subgraph_definitions: Vec<SubgraphDefinition>,
+    ) -> BoxFuture<'_, Option<SupergraphSdl<'_>>> {
+        Box::pin(HybridComposition::compose_services_without_satisfiability(
+            self,
+            subgraph_definitions,
+        ))
+    }
+
+    fn validate_satisfiability(&mut self) -> BoxFuture<'_, Result<Vec<Issue>, Vec<Issue>>> {
+        Box::pin(HybridComposition::validate_satisfiability(self))
+    }
+
+    fn update_supergraph_sdl(&mut self, supergraph_sdl: String) {
+        HybridComposition::update_supergraph_sdl(self, supergraph_sdl)
+    }
+
+    fn add_issues(&mut self, issues: &mut dyn Iterator<Item = Issue>) {
+        HybridComposition::add_issues(self, issues)
+    }
+
+    fn compose(&mut self, subgraph_definitions: Vec<SubgraphDefinition>) -> BoxFuture<'_, ()> {
+        Box::pin(HybridComposition::compose(self, subgraph_definitions))
+    }
+
+    fn experimental_compose<'a>(
+        self: Box<Self>,
+        subgraph_definitions: Vec<SubgraphDefinition>,
+    ) -> BoxFuture<'a, Result<PluginResult, Vec<BuildMessage>>>
+    where
+        Self: 'a,
+    {
+        Box::pin(HybridComposition::experimental_compose(
+            *self,
+            subgraph_definitions,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::block_on;
+
+    #[derive(Default)]
+    struct NoJavaScript {
+        issues: Vec<Issue>,
+    }
+
+    impl HybridComposition for NoJavaScript {
+        async fn compose_services_without_satisfiability(
+            &mut self,
+            _subgraph_definitions: Vec<SubgraphDefinition>,
+        ) -> Option<SupergraphSdl<'_>> {
+            None
+        }
+
+        async fn validate_satisfiability(&mut self) -> Result<Vec<Issue>, Vec<Issue>> {
+            Ok(vec![])
+        }
+
+        fn update_supergraph_sdl(&mut self, _supergraph_sdl: String) {}
+
+        fn add_issues<Source: Iterator<Item = Issue>>(&mut self, issues: Source) {
+            self.issues.extend(issues);
+        }
+    }
+
+    #[test]
+    fn it_can_compose_through_a_trait_object() {
+        let mut composition: Box<dyn DynHybridComposition> = Box::<NoJavaScript>::default();
+        composition.add_issues(&mut std::iter::empty());
+        block_on(composition.compose(vec![SubgraphDefinition {
+            name: "products".to_string(),
+            url: "http://products".to_string(),
+            sdl: "type Query { products: [String] }".to_string(),
+        }]));
+        assert!(block_on(composition.validate_satisfiability()).is_ok());
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::BTreeMap;
+
+use apollo_compiler::schema::ExtendedType;
+use apollo_compiler::{name, Name, Schema};
+use apollo_federation::link::spec::Identity;
+use apollo_federation::link::Link;
+use apollo_federation_types::composition::Issue;
+use apollo_federation_types::javascript::SubgraphDefinition;
+use serde::{Deserialize, Serialize};
+
+use crate::{validate_connector_subgraphs, ConnectorsValidationResult, SubgraphSchema};
+
+/// Every entity across a set of subgraphs, as returned by [`entity_inventory`].
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityInventory {
+    /// Sorted by entity name.
+    pub entities: Vec<Entity>,
+}
+
+/// A type with at least one `@key` in at least one subgraph.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Entity {
+    pub name: String,
+
+    /// The subgraphs which define this entity with a `@key`, sorted by subgraph name.
+    pub subgraphs: Vec<EntitySubgraph>,
+}
+
+/// How a single subgraph defines an entity.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntitySubgraph {
+    pub subgraph: String,
+    pub keys: Vec<EntityKey>,
+
+    /// Whether the router can fetch this entity from the subgraph, which is the case if any of
+    /// its keys is resolvable.
+    pub resolvable: bool,
+
+    /// Whether the subgraph defines this (interface) entity as an object with `@interfaceObject`.
+    pub interface_object: bool,
+}
+
+/// A single `@key` directive.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityKey {
+    /// The field set of the key, as written in the subgraph.
+    pub fields: String,
+    pub resolvable: bool,
+}
+
+/// Lists every entity defined by the given subgraphs, along with its keys in each subgraph.
+///
+/// Subgraphs are parsed and validated the same way as in the first phase of composition, so any
+/// errors from that phase are returned instead.
+pub fn entity_inventory(
+    subgraph_definitions: Vec<SubgraphDefinition>,
+) -> Result<EntityInventory, Vec<Issue>> {
+    let ConnectorsValidationResult {
+        parsed_subgraphs, ..
+    } = validate_connector_subgraphs(subgraph_definitions)?;
+
+    let mut entities: BTreeMap<String, Vec<EntitySubgraph>> = BTreeMap::new();
+    for (subgraph_name, SubgraphSchema { schema, .. }) in &parsed_subgraphs {
+        let key_name = federation_directive_name(schema, name!("key"));
+        let interface_object_name = federation_directive_name(schema, name!("interfaceObject"));
+        for (type_name, ty) in &schema.types {
+            if ty.is_built_in()
+                || !matches!(ty, ExtendedType::Object(_) | ExtendedType::Interface(_))
+            {
+                continue;
+            }
+            let keys: Vec<EntityKey> = ty
+                .directives()
+                .get_all(&key_name)
+                .filter_map(|directive| {
+                    Some(EntityKey {
+                        fields: directive
+                            .specified_argument_by_name("fields")?
+                            .as_str()?
+                            .to_string(),
+                        resolvable: directive
+                            .specified_argument_by_name("resolvable")
+                            .and_then(|value| value.to_bool())
+                            .unwrap_or(true),
+                    })
+                })
+                .collect();
+            if keys.is_empty() {
+                continue;
+            }
+            let interface_object = ty.directives().has(&interface_object_name);
+            entities
+                .entry(type_name.to_string())
+                .or_default()
+                .push(EntitySubgraph {
+                    subgraph: subgraph_name.clone(),
+                    resolvable: keys.iter().any(|key| key.resolvable),
+                    keys,
+                    interface_object,
+                });
+        }
+    }
+
+    Ok(EntityInventory {
+        entities: entities
+            .into_iter()
+            .map(|(name, mut subgraphs)| {
+                subgraphs.sort_by(|a, b| a.subgraph.cmp(&b.subgraph));
+                Entity { name, subgraphs }
+            })
+            .collect(),
+    })
+}
+
+/// The name of a federation directive in a subgraph, which depends on how its `@link` imports
+/// the federation spec. Subgraphs without the link (like federation 1 subgraphs) use the name as is.
+fn federation_directive_name(schema: &Schema, name: Name) -> Name {
+    match Link::for_identity(schema, &Identity::federation_identity()) {
+        Some((link, _)) => link.directive_name_in_schema(&name),
+        None => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_lists_entities_and_keys() {
+        let subgraphs = vec![
+            SubgraphDefinition {
+                name: "reviews".to_string(),
+                url: "http://reviews".to_string(),
+                sdl: r#"
+                    extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@interfaceObject"])
+                    type Product @key(fields: "id", resolvable: false) { id: ID! }
+                    type Media @key(fields: "id") @interfaceObject { id: ID! reviews: [String] }
+                    type Query { latest: Product }
+                "#
+                .to_string(),
+            },
+            SubgraphDefinition {
+                name: "products".to_string(),
+                url: "http://products".to_string(),
+                sdl: r#"
+                    extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key"])
+                    type Query { products: [Product] }
+                    type Product @key(fields: "id") @key(fields: "sku { code }") { id: ID! sku: Sku }
+                    type Sku { code: String }
+                    interface Media @key(fields: "id") { id: ID! }
+                "#
+                .to_string(),
+            },
+        ];
+
+        let inventory = entity_inventory(subgraphs).unwrap();
+        assert_eq!(
+            inventory,
+            EntityInventory {
+                entities: vec![
+                    Entity {
+                        name: "Media".to_string(),
+                        subgraphs: vec![
+                            EntitySubgraph {
+                                subgraph: "products".to_string(),
+                                keys: vec![EntityKey {
+                                    fields: "id".to_string(),
+                                    resolvable: true,
+                                }],
+                                resolvable: true,
+                                interface_object: false,
+                            },
+                            EntitySubgraph {
+                                subgraph: "reviews".to_string(),
+                                keys: vec![EntityKey {
+                                    fields: "id".to_string(),
+                                    resolvable: true,
+                                }],
+                                resolvable: true,
+                                interface_object: true,
+                            },
+                        ],
+                    },
+                    Entity {
+                        name: "Product".to_string(),
+                        subgraphs: vec![
+                            EntitySubgraph {
+                                subgraph: "products".to_string(),
+                                keys: vec![
+                                    EntityKey {
+                                        fields: "id".to_string(),
+                                        resolvable: true,
+                                    },
+                                    EntityKey {
+                                        fields: "sku { code }".to_string(),
+                                        resolvable: true,
+                                    },
+                                ],
+                                resolvable: true,
+                                interface_object: false,
+                            },
+                            EntitySubgraph {
+                                subgraph: "reviews".to_string(),
+                                keys: vec![EntityKey {
+                                    fields: "id".to_string(),
+                                    resolvable: false,
+                                }],
+                                resolvable: false,
+                                interface_object: false,
+                            },
+                        ],
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn it_finds_aliased_directives() {
+        let subgraphs = vec![SubgraphDefinition {
+            name: "products".to_string(),
+            url: "http://products".to_string(),
+            sdl: r#"
+                extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: [{ name: "@key", as: "@primaryKey" }])
+                directive @key(fields: String!) on OBJECT
+                type Query { products: [Product] }
+                type Product @primaryKey(fields: "id") @key(fields: "name") { id: ID! name: String }
+                type Media @primaryKey(fields: "id") @federation__interfaceObject { id: ID! }
+            "#
+            .to_string(),
+        }];
+
+        let inventory = entity_inventory(subgraphs).unwrap();
+        let keys: Vec<_> = inventory
+            .entities
+            .iter()
+            .map(|entity| {
+                let subgraph = &entity.subgraphs[0];
+                (
+                    entity.name.as_str(),
+                    subgraph
+                        .keys
+                        .iter()
+                        .map(|key| key.fields.as_str())
+                        .collect::<Vec<_>>(),
+                    subgraph.interface_object,
+                )
+            })
+            .collect();
+        assert_eq!(
+            keys,
+            vec![("Media", vec!["id"], true), ("Product", vec!["id"], false)]
+        );
+    }
+}
@@ -11,16 +11,34 @@ use apollo_federation::connectors::{
 use apollo_federation::internal_composition_api::validate_cache_tag_directives;
 use apollo_federation::subgraph::typestate::{Initial, Subgraph, Validated};
 use apollo_federation::subgraph::SubgraphError;
-use apollo_federation_types::build_plugin::PluginResult;
-use apollo_federation_types::composition::{MergeResult, SubgraphLocation};
+use apollo_federation_types::build_plugin::{BuildMessage, PluginResult};
+use apollo_federation_types::composition::{
+    MergeResult, SubgraphLocation, INTERNAL_ERROR, OVERRIDE_ON_CONNECTOR,
+};
 use apollo_federation_types::{
     composition::{Issue, Severity},
     javascript::SubgraphDefinition,
 };
 use std::collections::HashMap;
-use std::iter::once;
 use std::sync::Arc;
 
+mod dependency_graph;
+mod dynamic;
+mod inventory;
+mod limits;
+mod phase;
+mod repro;
+#[cfg(test)]
+mod test_support;
+
+pub use dependency_graph::{DependencyEdge, DependencyKind, SubgraphDependencyGraph};
+pub use dynamic::{BoxFuture, DynHybridComposition};
+pub use inventory::{entity_inventory, Entity, EntityInventory, EntityKey, EntitySubgraph};
+pub use limits::CompositionLimits;
+use phase::catch_panic;
+pub use phase::CompositionPhase;
+pub use repro::{experimental_compose_with_reproduction, ReproductionBundle};
+
 /// This trait includes all the Rust-side composition logic, plus hooks for the JavaScript side.
 /// If you implement the functions in this trait to build your own JavaScript interface, then you
 /// can call [`HybridComposition::compose`] to run the complete composition process.
@@ -57,9 +75,23 @@ pub trait HybridComposition {
     /// When the Rust composition/validation code finds issues, it will call this method to add
     /// them to the list of issues that will be returned to the user.
     ///
-    /// It's on the implementor of this trait to convert `From<Issue>`
+    /// It's on the implementor of this trait to convert `From<Issue>`. Issues found by the Rust
+    /// code already have their schema coordinate; issues from JavaScript can get theirs with
+    /// [`Issue::with_schema_coordinate_from_sdl`].
     fn add_issues<Source: Iterator<Item = Issue>>(&mut self, issues: Source);
 
+    /// The limits enforced by [`HybridComposition::experimental_compose`] on its inputs. Exceeding
+    /// any of them fails composition with a dedicated error [`Issue`].
+    ///
+    /// By default, composition is unlimited.
+    fn composition_limits(&self) -> CompositionLimits {
+        CompositionLimits::default()
+    }
+
+    /// Called by [`HybridComposition::experimental_compose`] as it starts each phase, for example
+    /// to trace progress or to record how far composition got before failing.
+    fn enter_phase(&mut self, _phase: CompositionPhase) {}
+
     /// Runs the complete composition process, hooking into both the Rust and JavaScript implementations.
     ///
     /// # Asyncness
@@ -74,9 +106,20 @@ pub trait HybridComposition {
     /// 3. Run Rust-based validation on the supergraph
     /// 4. Call [`validate_satisfiability`] to run JavaScript-based validation on the supergraph
     async fn compose(&mut self, subgraph_definitions: Vec<SubgraphDefinition>) {
+        // Kept so that issues can get schema coordinates from the SDL their locations are in
+        let original_subgraphs = subgraph_definitions.clone();
+        let with_coordinates = |issues: Vec<Issue>, subgraphs: &[SubgraphDefinition]| {
+            issues
+                .into_iter()
+                .map(|issue| issue.with_schema_coordinate_from_sdl(subgraphs))
+                .collect::<Vec<_>>()
+        };
+
         // `@cacheTag` directive validation
-        if let Err(cache_tag_errors) = validate_cache_tag_in_subgraphs(&subgraph_definitions) {
-            self.add_issues(cache_tag_errors.into_iter());
+        if let Err(cache_tag_errors) = catch_panic(CompositionPhase::CacheTagValidation, || {
+            validate_cache_tag_in_subgraphs(&subgraph_definitions)
+        }) {
+            self.add_issues(with_coordinates(cache_tag_errors, &original_subgraphs).into_iter());
             return;
         }
 
@@ -85,15 +128,19 @@ pub trait HybridComposition {
             subgraphs,
             parsed_subgraphs,
             hints: connector_hints,
-        } = match validate_connector_subgraphs(subgraph_definitions) {
+        } = match catch_panic(CompositionPhase::ConnectorsValidation, || {
+            validate_connector_subgraphs(subgraph_definitions)
+        }) {
             Ok(results) => results,
             Err(errors) => {
-                self.add_issues(errors.into_iter());
+                self.add_issues(with_coordinates(errors, &original_subgraphs).into_iter());
                 return;
             }
         };
-        self.add_issues(connector_hints.into_iter());
+        self.add_issues(with_coordinates(connector_hints, &original_subgraphs).into_iter());
 
+        // Satisfiability issues are located in the subgraphs JavaScript composed
+        let composed_subgraphs = subgraphs.clone();
         let Some(supergraph_sdl) = self
             .compose_services_without_satisfiability(subgraphs)
             .await
@@ -103,23 +150,27 @@ pub trait HybridComposition {
 
         // Any issues with overrides are fatal since they'll cause errors in expansion,
         // so we return early if we see any.
-        let override_errors = validate_overrides(parsed_subgraphs);
-        if !override_errors.is_empty() {
-            self.add_issues(override_errors.into_iter());
+        if let Err(override_errors) = catch_panic(CompositionPhase::OverrideValidation, || {
+            validate_overrides(parsed_subgraphs)
+        }) {
+            self.add_issues(with_coordinates(override_errors, &original_subgraphs).into_iter());
             return;
         }
 
-        let expansion_result = match expand_connectors(supergraph_sdl, &Default::default()) {
-            Ok(result) => result,
-            Err(err) => {
-                self.add_issues(once(Issue {
-                    code: "INTERNAL_ERROR".to_string(),
-                    message: format!(
+        let expansion_result = match catch_panic(CompositionPhase::ExpandConnectors, || {
+            expand_connectors(supergraph_sdl, &Default::default()).map_err(|err| {
+                vec![Issue::new(
+                    INTERNAL_ERROR,
+                    format!(
                         "Composition failed due to an internal error when expanding connectors, please report this: {err}"
                     ),
-                    locations: vec![],
-                    severity: Severity::Error,
-                }));
+                    Severity::Error,
+                )]
+            })
+        }) {
+            Ok(result) => result,
+            Err(errors) => {
+                self.add_issues(errors.into_iter());
                 return;
             }
         };
@@ -137,7 +188,7 @@ pub trait HybridComposition {
                 self.add_issues(
                     satisfiability_result_into_issues(satisfiability_result).map(|mut issue| {
                         sanitize_connectors_issue(&mut issue, by_service_name.iter());
-                        issue
+                        issue.with_schema_coordinate_from_sdl(&composed_subgraphs)
                     }),
                 );
 
@@ -145,7 +196,10 @@ pub trait HybridComposition {
             }
             ExpansionResult::Unchanged => {
                 let satisfiability_result = self.validate_satisfiability().await;
-                self.add_issues(satisfiability_result_into_issues(satisfiability_result));
+                self.add_issues(
+                    satisfiability_result_into_issues(satisfiability_result)
+                        .map(|issue| issue.with_schema_coordinate_from_sdl(&composed_subgraphs)),
+                );
             }
         }
     }
@@ -167,101 +221,143 @@ pub trait HybridComposition {
     /// 10. Validate satisfiability
     ///
     /// In case of a composition failure, we return a list of errors from the current composition
-    /// phase.
+    /// phase. Like hints, their locations have byte offsets into the subgraph SDL they were found
+    /// in, which is the upgraded SDL for errors from merging onwards.
+    ///
+    /// The [`HybridComposition::composition_limits`] are checked before any SDL is parsed and
+    /// again once the subgraphs have been parsed.
     async fn experimental_compose(
         mut self,
         subgraph_definitions: Vec<SubgraphDefinition>,
-    ) -> Result<PluginResult, Vec<Issue>>
+    ) -> Result<PluginResult, Vec<BuildMessage>>
     where
         Self: Sized,
     {
-        // `@cacheTag` directive validation
-        validate_cache_tag_in_subgraphs(&subgraph_definitions)?;
-
-        // connectors validations
-        // Any issues with overrides are fatal since they'll cause errors in expansion,
-        // so we return early if we see any.
-        // TODO those validations should be moved to subgraph validations in the apollo-federation crate instead
-        let ConnectorsValidationResult {
-            subgraphs: connected_subgraphs,
-            parsed_subgraphs,
-            hints: connector_hints,
-        } = validate_connector_subgraphs(subgraph_definitions)?;
-
-        let upgraded_subgraphs = self
-            .experimental_upgrade_subgraphs(connected_subgraphs)
-            .await?;
-
-        // merge
-        let merge_result = self
-            .experimental_merge_subgraphs(upgraded_subgraphs)
-            .await?;
-
-        // Extra connectors validation after merging.
-        // - So that connectors-related override errors will only be reported if merging was
-        //   successful.
-        let override_errors = validate_overrides(parsed_subgraphs);
-        if !override_errors.is_empty() {
-            return Err(override_errors);
-        }
-
-        // expand connectors as needed
-        let supergraph_sdl = merge_result.supergraph.clone();
-        let expansion_result = match expand_connectors(&supergraph_sdl, &Default::default()) {
-            Ok(result) => result,
-            Err(err) => {
-                return Err(vec![err.into()]);
-            }
-        };
-
-        // verify satisfiability
-        match expansion_result {
-            ExpansionResult::Expanded {
-                raw_sdl,
-                connectors: Connectors {
-                    by_service_name, ..
-                },
-                ..
-            } => {
-                self.experimental_validate_satisfiability(raw_sdl.as_str())
+        // Kept so that errors and hints can include byte offsets into the SDL they're in, which
+        // is the upgraded SDL once subgraphs have been upgraded
+        let original_subgraphs = subgraph_definitions.clone();
+        let mut located_subgraphs = subgraph_definitions.clone();
+        let result: Result<PluginResult, Vec<Issue>> = async {
+            self.enter_phase(CompositionPhase::InputLimits);
+            let limits = self.composition_limits();
+            limits.validate_subgraph_definitions(&subgraph_definitions)?;
+
+            // `@cacheTag` directive validation
+            self.enter_phase(CompositionPhase::CacheTagValidation);
+            catch_panic(CompositionPhase::CacheTagValidation, || {
+                validate_cache_tag_in_subgraphs(&subgraph_definitions)
+            })?;
+
+            // connectors validations
+            // Any issues with overrides are fatal since they'll cause errors in expansion,
+            // so we return early if we see any.
+            // TODO those validations should be moved to subgraph validations in the apollo-federation crate instead
+            self.enter_phase(CompositionPhase::ConnectorsValidation);
+            let ConnectorsValidationResult {
+                subgraphs: connected_subgraphs,
+                parsed_subgraphs,
+                hints: connector_hints,
+            } = catch_panic(CompositionPhase::ConnectorsValidation, || {
+                validate_connector_subgraphs(subgraph_definitions)
+            })?;
+            limits.validate_subgraph_schemas(
+                parsed_subgraphs
+                    .iter()
+                    .map(|(name, subgraph)| (name.as_str(), &subgraph.schema)),
+            )?;
+
+            self.enter_phase(CompositionPhase::UpgradeSubgraphs);
+            let upgraded_subgraphs = self
+                .experimental_upgrade_subgraphs(connected_subgraphs)
+                .await?;
+
+            located_subgraphs = upgraded_subgraphs.clone();
+
+            // merge
+            self.enter_phase(CompositionPhase::MergeSubgraphs);
+            let merge_result = self
+                .experimental_merge_subgraphs(upgraded_subgraphs)
+                .await?;
+
+            // Extra connectors validation after merging.
+            // - So that connectors-related override errors will only be reported if merging was
+            //   successful.
+            self.enter_phase(CompositionPhase::OverrideValidation);
+            catch_panic(CompositionPhase::OverrideValidation, || {
+                validate_overrides(parsed_subgraphs)
+            })?;
+
+            // expand connectors as needed
+            self.enter_phase(CompositionPhase::ExpandConnectors);
+            let supergraph_sdl = merge_result.supergraph.clone();
+            let expansion_result = catch_panic(CompositionPhase::ExpandConnectors, || {
+                expand_connectors(&supergraph_sdl, &Default::default())
+                    .map_err(|err| vec![err.into()])
+            })?;
+
+            // verify satisfiability
+            self.enter_phase(CompositionPhase::ValidateSatisfiability);
+            match expansion_result {
+                ExpansionResult::Expanded {
+                    raw_sdl,
+                    connectors:
+                        Connectors {
+                            by_service_name, ..
+                        },
+                    ..
+                } => {
+                    self.experimental_validate_satisfiability(raw_sdl.as_str())
+                        .await
+                        .map(|s| {
+                            let mut composition_hints = merge_result.hints;
+                            composition_hints.extend(s);
+
+                            let mut build_messages: Vec<_> = connector_hints
+                                .into_iter()
+                                .map(|h| h.into_build_message_with_sdl(&original_subgraphs))
+                                .collect();
+                            build_messages.extend(composition_hints.into_iter().map(|h| {
+                                let mut issue = Into::<Issue>::into(h);
+                                sanitize_connectors_issue(&mut issue, by_service_name.iter());
+                                issue.into_build_message_with_sdl(&located_subgraphs)
+                            }));
+                            // return original supergraph
+                            PluginResult::new(Ok(supergraph_sdl), build_messages)
+                        })
+                        .map_err(|err| {
+                            err.into_iter()
+                                .map(|mut issue| {
+                                    sanitize_connectors_issue(&mut issue, by_service_name.iter());
+                                    issue
+                                })
+                                .collect()
+                        })
+                }
+                ExpansionResult::Unchanged => self
+                    .experimental_validate_satisfiability(supergraph_sdl.as_str())
                     .await
                     .map(|s| {
-                        let mut composition_hints = merge_result.hints;
-                        composition_hints.extend(s);
-
-                        let mut build_messages: Vec<_> =
-                            connector_hints.into_iter().map(|h| h.into()).collect();
-                        build_messages.extend(composition_hints.into_iter().map(|h| {
-                            let mut issue = Into::<Issue>::into(h);
-                            sanitize_connectors_issue(&mut issue, by_service_name.iter());
-                            issue.into()
-                        }));
-                        // return original supergraph
-                        PluginResult::new(Ok(supergraph_sdl), build_messages)
-                    })
-                    .map_err(|err| {
-                        err.into_iter()
-                            .map(|mut issue| {
-                                sanitize_connectors_issue(&mut issue, by_service_name.iter());
-                                issue
+                        let mut hints = merge_result.hints;
+                        hints.extend(s);
+
+                        let build_messages: Vec<_> = hints
+                            .into_iter()
+                            .map(|h| {
+                                Into::<Issue>::into(h)
+                                    .into_build_message_with_sdl(&located_subgraphs)
                             })
-                            .collect()
-                    })
+                            .collect();
+                        PluginResult::new(Ok(supergraph_sdl), build_messages)
+                    }),
             }
-            ExpansionResult::Unchanged => self
-                .experimental_validate_satisfiability(supergraph_sdl.as_str())
-                .await
-                .map(|s| {
-                    let mut hints = merge_result.hints;
-                    hints.extend(s);
-
-                    let build_messages: Vec<_> = hints
-                        .into_iter()
-                        .map(|h| Into::<Issue>::into(h).into())
-                        .collect();
-                    PluginResult::new(Ok(supergraph_sdl), build_messages)
-                }),
         }
+        .await;
+        result.map_err(|issues| {
+            issues
+                .into_iter()
+                .map(|issue| issue.into_build_message_with_sdl(&located_subgraphs))
+                .collect()
+        })
     }
 
     /// Maps to buildSubgraph & upgradeSubgraphsIfNecessary and performs following steps
@@ -275,22 +371,24 @@ pub trait HybridComposition {
         &mut self,
         subgraphs: Vec<SubgraphDefinition>,
     ) -> Result<Vec<SubgraphDefinition>, Vec<Issue>> {
-        let mut issues: Vec<Issue> = vec![];
-        let initial: Vec<Subgraph<Initial>> = subgraphs
-            .into_iter()
-            .map(|s| s.try_into())
-            .filter_map(|r| {
-                r.map_err(|e: SubgraphError| issues.extend(convert_subgraph_error_to_issues(e)))
-                    .ok()
-            })
-            .collect();
-        if !issues.is_empty() {
-            return Err(issues);
-        }
-        expand_subgraphs(initial)
-            .and_then(upgrade_subgraphs_if_necessary)
-            .map(|subgraphs| subgraphs.into_iter().map(|s| s.into()).collect())
-            .map_err(|errors| errors.into_iter().map(Issue::from).collect::<Vec<_>>())
+        catch_panic(CompositionPhase::UpgradeSubgraphs, || {
+            let mut issues: Vec<Issue> = vec![];
+            let initial: Vec<Subgraph<Initial>> = subgraphs
+                .into_iter()
+                .map(|s| s.try_into())
+                .filter_map(|r| {
+                    r.map_err(|e: SubgraphError| issues.extend(convert_subgraph_error_to_issues(e)))
+                        .ok()
+                })
+                .collect();
+            if !issues.is_empty() {
+                return Err(issues);
+            }
+            expand_subgraphs(initial)
+                .and_then(upgrade_subgraphs_if_necessary)
+                .map(|subgraphs| subgraphs.into_iter().map(|s| s.into()).collect())
+                .map_err(|errors| errors.into_iter().map(Issue::from).collect::<Vec<_>>())
+        })
     }
 
     /// In case of a merge failure, returns a list of errors.
@@ -298,33 +396,36 @@ pub trait HybridComposition {
         &mut self,
         subgraphs: Vec<SubgraphDefinition>,
     ) -> Result<MergeResult, Vec<Issue>> {
-        let mut subgraph_errors = vec![];
-        let validated: Vec<Subgraph<Validated>> = subgraphs
-            .into_iter()
-            .map(assume_subgraph_validated)
-            .filter_map(|r| {
-                r.map_err(|e| subgraph_errors.extend(convert_subgraph_error_to_issues(e)))
-                    .ok()
+        let options = self.composition_limits().composition_options();
+        catch_panic(CompositionPhase::MergeSubgraphs, || {
+            let mut subgraph_errors = vec![];
+            let validated: Vec<Subgraph<Validated>> = subgraphs
+                .into_iter()
+                .map(assume_subgraph_validated)
+                .filter_map(|r| {
+                    r.map_err(|e| subgraph_errors.extend(convert_subgraph_error_to_issues(e)))
+                        .ok()
+                })
+                .collect();
+            if !subgraph_errors.is_empty() {
+                // this should never happen
+                return Err(subgraph_errors);
+            }
+            pre_merge_validations(&validated)
+                .map_err(|errors| errors.into_iter().map(Issue::from).collect::<Vec<_>>())?;
+            let supergraph = merge_subgraphs(validated, &options)
+                .map_err(|errors| errors.into_iter().map(Issue::from).collect::<Vec<_>>())?;
+            post_merge_validations(&supergraph)
+                .map_err(|errors| errors.into_iter().map(Issue::from).collect::<Vec<_>>())?;
+            let hints = supergraph
+                .hints()
+                .iter()
+                .map(|hint| hint.clone().into())
+                .collect();
+            Ok(MergeResult {
+                supergraph: supergraph.schema().schema().to_string(),
+                hints,
             })
-            .collect();
-        if !subgraph_errors.is_empty() {
-            // this should never happen
-            return Err(subgraph_errors);
-        }
-        pre_merge_validations(&validated)
-            .map_err(|errors| errors.into_iter().map(Issue::from).collect::<Vec<_>>())?;
-        let supergraph = merge_subgraphs(validated, &Default::default())
-            .map_err(|errors| errors.into_iter().map(Issue::from).collect::<Vec<_>>())?;
-        post_merge_validations(&supergraph)
-            .map_err(|errors| errors.into_iter().map(Issue::from).collect::<Vec<_>>())?;
-        let hints = supergraph
-            .hints()
-            .iter()
-            .map(|hint| hint.clone().into())
-            .collect();
-        Ok(MergeResult {
-            supergraph: supergraph.schema().schema().to_string(),
-            hints,
         })
     }
 
@@ -333,10 +434,13 @@ pub trait HybridComposition {
         &mut self,
         supergraph_sdl: &str,
     ) -> Result<Vec<Issue>, Vec<Issue>> {
-        let supergraph = Supergraph::parse(supergraph_sdl).map_err(|e| vec![Issue::from(e)])?;
-        validate_satisfiability(supergraph, &Default::default())
-            .map(|s| s.hints().iter().map(|h| h.clone().into()).collect())
-            .map_err(|errors| errors.into_iter().map(Issue::from).collect::<Vec<_>>())
+        let options = self.composition_limits().composition_options();
+        catch_panic(CompositionPhase::ValidateSatisfiability, || {
+            let supergraph = Supergraph::parse(supergraph_sdl).map_err(|e| vec![Issue::from(e)])?;
+            validate_satisfiability(supergraph, &options)
+                .map(|s| s.hints().iter().map(|h| h.clone().into()).collect())
+                .map_err(|errors| errors.into_iter().map(Issue::from).collect::<Vec<_>>())
+        })
     }
 }
 
@@ -368,10 +472,13 @@ fn validate_connector_subgraphs(
             } = validate(subgraph.sdl, &subgraph.name);
             subgraph.sdl = transformed;
             for error in errors {
-                let issue = Issue {
-                    code: error.code.to_string(),
-                    message: error.message,
-                    locations: error
+                let issue = Issue::new(
+                    error.code.to_string(),
+                    error.message,
+                    convert_severity(error.code.severity()),
+                )
+                .with_locations(
+                    error
                         .locations
                         .into_iter()
                         .map(|range| SubgraphLocation {
@@ -379,8 +486,7 @@ fn validate_connector_subgraphs(
                             range: Some(range),
                         })
                         .collect(),
-                    severity: convert_severity(error.code.severity()),
-                };
+                );
                 if issue.severity == Severity::Error {
                     subgraph_validation_errors.push(issue);
                 } else {
@@ -414,7 +520,7 @@ fn validate_connector_subgraphs(
 /// expanding connectors; the supergraph may omit overridden fields and other shenanigans.
 /// To allow for a better developer experience, we check here if any connector-enabled subgraphs
 /// have fields overridden.
-fn validate_overrides(schemas: HashMap<String, SubgraphSchema>) -> Vec<Issue> {
+fn validate_overrides(schemas: HashMap<String, SubgraphSchema>) -> Result<(), Vec<Issue>> {
     let mut override_errors = Vec::new();
     for (subgraph_name, SubgraphSchema { schema, .. }) in &schemas {
         // We need to grab all fields in the schema since only fields can have the @override
@@ -468,22 +574,29 @@ fn validate_overrides(schemas: HashMap<String, SubgraphSchema>) -> Vec<Issue> {
                 .get(overridden_subgraph_name)
                 .is_some_and(|schema| schema.has_connectors)
             {
-                override_errors.push(Issue {
-                        code: "OVERRIDE_ON_CONNECTOR".to_string(),
-                        message: format!(
+                override_errors.push(
+                    Issue::new(
+                        OVERRIDE_ON_CONNECTOR,
+                        format!(
                             r#"Field "{field}" on subgraph "{subgraph_name}" is trying to override connector-enabled subgraph "{overridden_subgraph_name}", which is not yet supported. See https://go.apollo.dev/connectors/limitations#override-is-partially-unsupported"#,
                         ),
-                        locations: vec![SubgraphLocation {
-                            subgraph: Some(String::from(overridden_subgraph_name)),
-                            range: directive.line_column_range(&schema.sources),
-                        }],
-                        severity: Severity::Error,
-                    });
+                        Severity::Error,
+                    )
+                    .with_locations(vec![SubgraphLocation {
+                        subgraph: Some(String::from(overridden_subgraph_name)),
+                        range: directive.line_column_range(&schema.sources),
+                    }])
+                    .with_schema_coordinate(field),
+                );
             }
         }
     }
 
-    override_errors
+    if override_errors.is_empty() {
+        Ok(())
+    } else {
+        Err(override_errors)
+    }
 }
 
 fn sanitize_connectors_issue<'a>(
@@ -515,20 +628,17 @@ fn validate_cache_tag_in_subgraphs(
             Ok(res) => {
                 if !res.errors.is_empty() {
                     issues.extend(res.errors.into_iter().map(|err| {
-                        Issue {
-                            code: err.code().to_string(),
-                            message: err.message().to_string(),
-                            locations: err
-                                .locations()
-                                .iter()
-                                .cloned()
-                                .map(|range| SubgraphLocation {
-                                    subgraph: Some(subgraph_def.name.clone()),
-                                    range: Some(range),
-                                })
-                                .collect(),
-                            severity: Severity::Error,
-                        }
+                        Issue::new(err.code().to_string(), err.message(), Severity::Error)
+                            .with_locations(
+                                err.locations()
+                                    .iter()
+                                    .cloned()
+                                    .map(|range| SubgraphLocation {
+                                        subgraph: Some(subgraph_def.name.clone()),
+                                        range: Some(range),
+                                    })
+                                    .collect(),
+                            )
                     }));
                 }
             }
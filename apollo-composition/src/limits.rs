@@ -0,0 +1,224 @@
+use apollo_compiler::{schema::ExtendedType, Schema};
+use apollo_federation::composition::CompositionOptions;
+use apollo_federation_types::composition::{
+    Issue, Severity, SubgraphLocation, MAX_SUBGRAPHS_EXCEEDED, MAX_SUBGRAPH_FIELDS_EXCEEDED,
+    MAX_SUBGRAPH_SDL_BYTES_EXCEEDED, MAX_SUBGRAPH_TYPES_EXCEEDED,
+};
+use apollo_federation_types::javascript::SubgraphDefinition;
+use serde::{Deserialize, Serialize};
+
+/// Limits on the size of the inputs accepted by [`crate::HybridComposition::experimental_compose`],
+/// so that a pathological set of subgraphs fails with an [`Issue`] instead of exhausting memory.
+///
+/// Every limit is optional, and `None` (the default) means unlimited.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompositionLimits {
+    /// The maximum number of subgraphs that can be composed together.
+    pub max_subgraphs: Option<usize>,
+
+    /// The maximum size of a single subgraph's SDL, in bytes.
+    pub max_subgraph_sdl_bytes: Option<usize>,
+
+    /// The maximum number of types a single subgraph may define. Built-in types are not counted.
+    pub max_subgraph_types: Option<usize>,
+
+    /// The maximum number of fields (including input fields) a single subgraph may define.
+    pub max_subgraph_fields: Option<usize>,
+
+    /// The maximum number of outstanding subgraph paths to validate during satisfiability,
+    /// which bounds how much work satisfiability is allowed to do.
+    pub max_validation_subgraph_paths: Option<usize>,
+}
+
+impl CompositionLimits {
+    /// Checks the limits that can be enforced before any SDL is parsed.
+    pub(crate) fn validate_subgraph_definitions(
+        &self,
+        subgraph_definitions: &[SubgraphDefinition],
+    ) -> Result<(), Vec<Issue>> {
+        let mut issues = Vec::new();
+        if let Some(max_subgraphs) = self.max_subgraphs {
+            if subgraph_definitions.len() > max_subgraphs {
+                issues.push(Issue::new(
+                    MAX_SUBGRAPHS_EXCEEDED,
+                    format!(
+                        "Composition received {} subgraphs, which exceeds the limit of {max_subgraphs}.",
+                        subgraph_definitions.len()
+                    ),
+                    Severity::Error,
+                ));
+            }
+        }
+        if let Some(max_sdl_bytes) = self.max_subgraph_sdl_bytes {
+            for subgraph in subgraph_definitions {
+                if subgraph.sdl.len() > max_sdl_bytes {
+                    issues.push(limit_exceeded(
+                        MAX_SUBGRAPH_SDL_BYTES_EXCEEDED,
+                        &subgraph.name,
+                        format!(
+                            "Schema is {} bytes, which exceeds the limit of {max_sdl_bytes} bytes.",
+                            subgraph.sdl.len()
+                        ),
+                    ));
+                }
+            }
+        }
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+
+    /// Checks the limits that need a parsed schema for each subgraph.
+    pub(crate) fn validate_subgraph_schemas<'a>(
+        &self,
+        schemas: impl IntoIterator<Item = (&'a str, &'a Schema)>,
+    ) -> Result<(), Vec<Issue>> {
+        if self.max_subgraph_types.is_none() && self.max_subgraph_fields.is_none() {
+            return Ok(());
+        }
+        let mut schemas: Vec<_> = schemas.into_iter().collect();
+        schemas.sort_by_key(|(name, _)| *name);
+
+        let mut issues = Vec::new();
+        for (subgraph_name, schema) in schemas {
+            let types = schema.types.values().filter(|ty| !ty.is_built_in());
+            if let Some(max_types) = self.max_subgraph_types {
+                let type_count = types.clone().count();
+                if type_count > max_types {
+                    issues.push(limit_exceeded(
+                        MAX_SUBGRAPH_TYPES_EXCEEDED,
+                        subgraph_name,
+                        format!(
+                            "Schema defines {type_count} types, which exceeds the limit of {max_types}."
+                        ),
+                    ));
+                }
+            }
+            if let Some(max_fields) = self.max_subgraph_fields {
+                let field_count: usize = types.map(count_fields).sum();
+                if field_count > max_fields {
+                    issues.push(limit_exceeded(
+                        MAX_SUBGRAPH_FIELDS_EXCEEDED,
+                        subgraph_name,
+                        format!(
+                            "Schema defines {field_count} fields, which exceeds the limit of {max_fields}."
+                        ),
+                    ));
+                }
+            }
+        }
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+
+    pub(crate) fn composition_options(&self) -> CompositionOptions {
+        CompositionOptions {
+            max_validation_subgraph_paths: self.max_validation_subgraph_paths,
+        }
+    }
+}
+
+fn count_fields(ty: &ExtendedType) -> usize {
+    match ty {
+        ExtendedType::Object(node) => node.fields.len(),
+        ExtendedType::Interface(node) => node.fields.len(),
+        ExtendedType::InputObject(node) => node.fields.len(),
+        ExtendedType::Scalar(_) | ExtendedType::Union(_) | ExtendedType::Enum(_) => 0,
+    }
+}
+
+fn limit_exceeded(code: &str, subgraph_name: &str, message: String) -> Issue {
+    Issue::new(code, message, Severity::Error).with_locations(vec![SubgraphLocation {
+        subgraph: Some(subgraph_name.to_string()),
+        range: None,
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subgraph(name: &str, sdl: &str) -> SubgraphDefinition {
+        SubgraphDefinition {
+            name: name.to_string(),
+            url: format!("http://{name}"),
+            sdl: sdl.to_string(),
+        }
+    }
+
+    #[test]
+    fn it_allows_everything_by_default() {
+        let subgraphs = vec![
+            subgraph("a", "type Query { a: String }"),
+            subgraph("b", "type Query { b: String }"),
+        ];
+        let schema = Schema::parse("type Query { a: String }", "a.graphql").unwrap();
+        let limits = CompositionLimits::default();
+        assert!(limits.validate_subgraph_definitions(&subgraphs).is_ok());
+        assert!(limits.validate_subgraph_schemas([("a", &schema)]).is_ok());
+    }
+
+    #[test]
+    fn it_limits_subgraph_count_and_size() {
+        let subgraphs = vec![
+            subgraph("a", "type Query { a: String }"),
+            subgraph("b", "type Query { b: String, c: String }"),
+        ];
+        let limits = CompositionLimits {
+            max_subgraphs: Some(1),
+            max_subgraph_sdl_bytes: Some(30),
+            ..Default::default()
+        };
+        let codes: Vec<_> = limits
+            .validate_subgraph_definitions(&subgraphs)
+            .unwrap_err()
+            .into_iter()
+            .map(|issue| (issue.code, issue.locations.first().cloned()))
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                (MAX_SUBGRAPHS_EXCEEDED.to_string(), None),
+                (
+                    MAX_SUBGRAPH_SDL_BYTES_EXCEEDED.to_string(),
+                    Some(SubgraphLocation {
+                        subgraph: Some("b".to_string()),
+                        range: None,
+                    })
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_limits_types_and_fields() {
+        let small = Schema::parse("type Query { a: String }", "small.graphql").unwrap();
+        let large = Schema::parse(
+            "type Query { a: A } type A { b: String, c: String } input I { d: Int }",
+            "large.graphql",
+        )
+        .unwrap();
+        let limits = CompositionLimits {
+            max_subgraph_types: Some(2),
+            max_subgraph_fields: Some(3),
+            ..Default::default()
+        };
+        let issues = limits
+            .validate_subgraph_schemas([("small", &small), ("large", &large)])
+            .unwrap_err();
+        assert_eq!(
+            issues.iter().map(|i| i.code.as_str()).collect::<Vec<_>>(),
+            vec![MAX_SUBGRAPH_TYPES_EXCEEDED, MAX_SUBGRAPH_FIELDS_EXCEEDED]
+        );
+        assert_eq!(
+            issues[0].message,
+            "Schema defines 3 types, which exceeds the limit of 2."
+        );
+    }
+}
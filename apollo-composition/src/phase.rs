@@ -0,0 +1,91 @@
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use apollo_federation_types::composition::{Issue, Severity, INTERNAL_ERROR};
+use serde::{Deserialize, Serialize};
+
+/// The Rust-side steps of composition, in the order they run.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CompositionPhase {
+    InputLimits,
+    CacheTagValidation,
+    ConnectorsValidation,
+    UpgradeSubgraphs,
+    MergeSubgraphs,
+    OverrideValidation,
+    ExpandConnectors,
+    ValidateSatisfiability,
+}
+
+impl Display for CompositionPhase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            CompositionPhase::InputLimits => "input limits validation",
+            CompositionPhase::CacheTagValidation => "@cacheTag validation",
+            CompositionPhase::ConnectorsValidation => "connectors validation",
+            CompositionPhase::UpgradeSubgraphs => "subgraph upgrade",
+            CompositionPhase::MergeSubgraphs => "subgraph merge",
+            CompositionPhase::OverrideValidation => "override validation",
+            CompositionPhase::ExpandConnectors => "connectors expansion",
+            CompositionPhase::ValidateSatisfiability => "satisfiability validation",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Runs a single phase of composition, turning a panic into an `INTERNAL_ERROR` issue so that a
+/// bug in `apollo-federation` can't take down a long-running host process.
+pub(crate) fn catch_panic<T>(
+    phase: CompositionPhase,
+    f: impl FnOnce() -> Result<T, Vec<Issue>>,
+) -> Result<T, Vec<Issue>> {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        Err(vec![Issue::new(
+            INTERNAL_ERROR,
+            format!(
+                "Composition failed due to an internal error during {phase}, please report this: {}",
+                panic_message(payload.as_ref())
+            ),
+            Severity::Error,
+        )])
+    })
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic payload"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_converts_panics_into_issues() {
+        let result: Result<(), _> = catch_panic(CompositionPhase::MergeSubgraphs, || {
+            panic!("unexpected {} directive", "@key")
+        });
+        let issues = result.unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, INTERNAL_ERROR);
+        assert_eq!(
+            issues[0].message,
+            "Composition failed due to an internal error during subgraph merge, please report this: unexpected @key directive"
+        );
+    }
+
+    #[test]
+    fn it_passes_through_results() {
+        assert_eq!(
+            catch_panic(CompositionPhase::ExpandConnectors, || Ok(1)),
+            Ok(1)
+        );
+    }
+}
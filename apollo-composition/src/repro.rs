@@ -0,0 +1,314 @@
+use std::cell::Cell;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::rc::Rc;
+
+use apollo_federation_types::build_plugin::{BuildMessage, PluginResult};
+use apollo_federation_types::composition::{Issue, MergeResult};
+use apollo_federation_types::javascript::SubgraphDefinition;
+use serde::{Deserialize, Serialize};
+
+use crate::{CompositionLimits, CompositionPhase, HybridComposition, SupergraphSdl};
+
+/// The version of `apollo-federation` this crate is built against, from the exact version it's
+/// pinned to (see `build.rs`).
+const APOLLO_FEDERATION_VERSION: &str = env!("APOLLO_FEDERATION_VERSION");
+
+/// Everything needed to reproduce a run of [`HybridComposition::experimental_compose`], so that an
+/// `INTERNAL_ERROR` can be reported as a single JSON file and replayed with
+/// [`ReproductionBundle::replay`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReproductionBundle {
+    pub apollo_composition_version: String,
+    pub apollo_federation_version: String,
+    pub subgraphs: Vec<SubgraphDefinition>,
+    pub limits: CompositionLimits,
+
+    /// The last phase composition entered, which is the one that failed if composition failed.
+    pub phase: Option<CompositionPhase>,
+
+    /// Every error and hint produced by composition.
+    pub build_messages: Vec<BuildMessage>,
+}
+
+impl ReproductionBundle {
+    /// Creates a bundle for the given inputs, which hasn't been run yet.
+    pub fn new(subgraphs: Vec<SubgraphDefinition>, limits: CompositionLimits) -> Self {
+        Self {
+            apollo_composition_version: env!("CARGO_PKG_VERSION").to_string(),
+            apollo_federation_version: APOLLO_FEDERATION_VERSION.to_string(),
+            subgraphs,
+            limits,
+            phase: None,
+            build_messages: vec![],
+        }
+    }
+
+    /// Parses a bundle previously written by [`ReproductionBundle::to_json`].
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Serializes the bundle as pretty-printed JSON.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Reads a bundle previously written by [`ReproductionBundle::write_to_file`].
+    pub fn read_from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        Ok(Self::from_json(&json)?)
+    }
+
+    /// Writes the bundle to a self-contained JSON file.
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_json()?)
+    }
+
+    /// Runs [`HybridComposition::experimental_compose`] again on the recorded subgraphs, with the
+    /// recorded limits.
+    ///
+    /// Only the Rust implementation of each phase is used, so this reproduces issues in
+    /// `apollo-federation` but not in a custom implementation of the experimental phases.
+    pub async fn replay(&self) -> Result<PluginResult, Vec<BuildMessage>> {
+        NativeComposition {
+            limits: self.limits.clone(),
+        }
+        .experimental_compose(self.subgraphs.clone())
+        .await
+    }
+}
+
+/// Runs [`HybridComposition::experimental_compose`] while recording a [`ReproductionBundle`] of
+/// its inputs and outcome, which can be attached to a bug report when composition fails with an
+/// `INTERNAL_ERROR`.
+pub async fn experimental_compose_with_reproduction<C: HybridComposition>(
+    composition: C,
+    subgraph_definitions: Vec<SubgraphDefinition>,
+) -> (Result<PluginResult, Vec<BuildMessage>>, ReproductionBundle) {
+    let mut bundle = ReproductionBundle::new(
+        subgraph_definitions.clone(),
+        composition.composition_limits(),
+    );
+    let phase = Rc::new(Cell::new(None));
+    let result = Recording {
+        inner: composition,
+        phase: phase.clone(),
+    }
+    .experimental_compose(subgraph_definitions)
+    .await;
+
+    bundle.phase = phase.get();
+    bundle.build_messages = match &result {
+        Ok(plugin_result) => plugin_result.build_messages.clone(),
+        Err(build_messages) => build_messages.clone(),
+    };
+    (result, bundle)
+}
+
+/// Forwards everything to another [`HybridComposition`], keeping track of the current phase.
+struct Recording<C> {
+    inner: C,
+    phase: Rc<Cell<Option<CompositionPhase>>>,
+}
+
+impl<C: HybridComposition> HybridComposition for Recording<C> {
+    async fn compose_services_without_satisfiability(
+        &mut self,
+        subgraph_definitions: Vec<SubgraphDefinition>,
+    ) -> Option<SupergraphSdl<'_>> {
+        self.inner
+            .compose_services_without_satisfiability(subgraph_definitions)
+            .await
+    }
+
+    async fn validate_satisfiability(&mut self) -> Result<Vec<Issue>, Vec<Issue>> {
+        self.inner.validate_satisfiability().await
+    }
+
+    fn update_supergraph_sdl(&mut self, supergraph_sdl: String) {
+        self.inner.update_supergraph_sdl(supergraph_sdl)
+    }
+
+    fn add_issues<Source: Iterator<Item = Issue>>(&mut self, issues: Source) {
+        self.inner.add_issues(issues)
+    }
+
+    fn composition_limits(&self) -> CompositionLimits {
+        self.inner.composition_limits()
+    }
+
+    fn enter_phase(&mut self, phase: CompositionPhase) {
+        self.phase.set(Some(phase));
+        self.inner.enter_phase(phase)
+    }
+
+    async fn experimental_upgrade_subgraphs(
+        &mut self,
+        subgraphs: Vec<SubgraphDefinition>,
+    ) -> Result<Vec<SubgraphDefinition>, Vec<Issue>> {
+        self.inner.experimental_upgrade_subgraphs(subgraphs).await
+    }
+
+    async fn experimental_merge_subgraphs(
+        &mut self,
+        subgraphs: Vec<SubgraphDefinition>,
+    ) -> Result<MergeResult, Vec<Issue>> {
+        self.inner.experimental_merge_subgraphs(subgraphs).await
+    }
+
+    async fn experimental_validate_satisfiability(
+        &mut self,
+        supergraph_sdl: &str,
+    ) -> Result<Vec<Issue>, Vec<Issue>> {
+        self.inner
+            .experimental_validate_satisfiability(supergraph_sdl)
+            .await
+    }
+}
+
+/// A [`HybridComposition`] which only supports the experimental, Rust-only composition.
+struct NativeComposition {
+    limits: CompositionLimits,
+}
+
+impl HybridComposition for NativeComposition {
+    async fn compose_services_without_satisfiability(
+        &mut self,
+        _subgraph_definitions: Vec<SubgraphDefinition>,
+    ) -> Option<SupergraphSdl<'_>> {
+        None
+    }
+
+    async fn validate_satisfiability(&mut self) -> Result<Vec<Issue>, Vec<Issue>> {
+        Ok(vec![])
+    }
+
+    fn update_supergraph_sdl(&mut self, _supergraph_sdl: String) {}
+
+    fn add_issues<Source: Iterator<Item = Issue>>(&mut self, _issues: Source) {}
+
+    fn composition_limits(&self) -> CompositionLimits {
+        self.limits.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::block_on;
+    use apollo_federation_types::composition::MAX_SUBGRAPHS_EXCEEDED;
+
+    fn subgraphs() -> Vec<SubgraphDefinition> {
+        vec![
+            SubgraphDefinition {
+                name: "products".to_string(),
+                url: "http://products".to_string(),
+                sdl: r#"
+                    extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key"])
+                    type Query { products: [Product] }
+                    type Product @key(fields: "id") { id: ID! name: String }
+                "#
+                .to_string(),
+            },
+            SubgraphDefinition {
+                name: "reviews".to_string(),
+                url: "http://reviews".to_string(),
+                sdl: r#"
+                    extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key"])
+                    type Product @key(fields: "id") { id: ID! reviews: [String] }
+                "#
+                .to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn it_records_the_failing_phase() {
+        let limits = CompositionLimits {
+            max_subgraphs: Some(1),
+            ..Default::default()
+        };
+        let (result, bundle) = block_on(experimental_compose_with_reproduction(
+            NativeComposition {
+                limits: limits.clone(),
+            },
+            subgraphs(),
+        ));
+        assert!(result.is_err());
+        assert_eq!(bundle.phase, Some(CompositionPhase::InputLimits));
+        assert_eq!(bundle.limits, limits);
+        assert_eq!(bundle.subgraphs, subgraphs());
+        assert_eq!(
+            bundle.build_messages[0].code.as_deref(),
+            Some(MAX_SUBGRAPHS_EXCEEDED)
+        );
+
+        let replayed = block_on(bundle.replay()).unwrap_err();
+        assert_eq!(replayed[0].code.as_deref(), Some(MAX_SUBGRAPHS_EXCEEDED));
+    }
+
+    #[test]
+    fn it_returns_errors_with_offsets_into_the_sdl_they_are_in() {
+        let mut subgraphs = subgraphs();
+        subgraphs[1].sdl = subgraphs[1]
+            .sdl
+            .replace("reviews: [String]", "\"é😀\" name: Int");
+        let errors = block_on(
+            NativeComposition {
+                limits: Default::default(),
+            }
+            .experimental_compose(subgraphs.clone()),
+        )
+        .unwrap_err();
+        let sharing = errors
+            .iter()
+            .find(|error| error.code.as_deref() == Some("INVALID_FIELD_SHARING"))
+            .unwrap();
+        assert_eq!(sharing.schema_coordinate.as_deref(), Some("Product.name"));
+        // Merge errors are located in the upgraded SDL, which has the federation definitions
+        let upgraded: Vec<_> = block_on(
+            NativeComposition {
+                limits: Default::default(),
+            }
+            .experimental_upgrade_subgraphs(subgraphs),
+        )
+        .unwrap();
+        let fields: Vec<_> = sharing
+            .locations
+            .iter()
+            .map(|location| {
+                let sdl = &upgraded
+                    .iter()
+                    .find(|subgraph| Some(&subgraph.name) == location.subgraph.as_ref())
+                    .unwrap()
+                    .sdl;
+                let start = location.start.as_ref().unwrap().start.unwrap();
+                let end = location.end.as_ref().unwrap().end.unwrap();
+                &sdl[start..end]
+            })
+            .collect();
+        assert_eq!(fields, ["name: String", "\"\"\"é😀\"\"\"\n  name: Int"]);
+    }
+
+    #[test]
+    fn it_round_trips_through_a_file() {
+        let (result, bundle) = block_on(experimental_compose_with_reproduction(
+            NativeComposition {
+                limits: Default::default(),
+            },
+            subgraphs(),
+        ));
+        assert!(result.is_ok());
+        assert_eq!(bundle.phase, Some(CompositionPhase::ValidateSatisfiability));
+
+        let dir = assert_fs::TempDir::new().unwrap();
+        let path = dir.path().join("repro.json");
+        bundle.write_to_file(&path).unwrap();
+        let read = ReproductionBundle::read_from_file(&path).unwrap();
+        assert_eq!(read, bundle);
+        assert!(block_on(read.replay()).is_ok());
+    }
+}
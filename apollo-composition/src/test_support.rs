@@ -0,0 +1,16 @@
+//! Helpers shared by the tests of several modules.
+
+use std::future::Future;
+use std::task::{Context, Poll, Waker};
+
+/// Runs a future to completion on the current thread. Composition never actually waits on
+/// anything in tests, so this just polls until the future is ready.
+pub(crate) fn block_on<T>(future: impl Future<Output = T>) -> T {
+    let mut future = std::pin::pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
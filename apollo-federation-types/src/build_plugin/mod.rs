@@ -2,6 +2,7 @@
 
 mod build_message;
 mod plugin_result;
+mod sarif;
 
 pub use build_message::BuildMessage;
 pub use build_message::BuildMessageLevel;
@@ -9,3 +10,9 @@ pub use build_message::BuildMessageLocation;
 pub use build_message::BuildMessagePoint;
 pub use plugin_result::PluginFailureReason;
 pub use plugin_result::PluginResult;
+#[cfg(feature = "composition")]
+pub use sarif::issues_to_sarif;
+pub use sarif::to_sarif;
+
+/// The key of a location's range in its file in the `other` map of a [`BuildMessageLocation`].
+pub(crate) const FILE_RANGE_KEY: &str = "fileRange";
@@ -0,0 +1,429 @@
+//! Conversion of build messages to [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html),
+//! so they can be uploaded to code-scanning tools.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use super::{BuildMessage, BuildMessageLevel, BuildMessageLocation, PluginResult, FILE_RANGE_KEY};
+use crate::javascript::SubgraphDefinition;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const TOOL_NAME: &str = "apollo-federation";
+const TOOL_INFORMATION_URI: &str = "https://www.apollographql.com/docs/federation/";
+/// The base URI which artifact locations are relative to, set to the config directory.
+const CONFIG_DIR_BASE_ID: &str = "CONFIG_DIR";
+
+/// Converts build messages to a SARIF log with a single run.
+///
+/// Each distinct code becomes a rule. Locations in subgraphs found in `subgraph_files` (see
+/// `SupergraphConfig::subgraph_files`) become physical locations in that file, and so do
+/// locations with the file they're in as their `source` (see
+/// `Issue::into_build_message_with_source_maps`). Files are relative to `config_dir`, which is
+/// the run's `CONFIG_DIR` base URI. Every location is also reported as a logical location named
+/// after its subgraph.
+///
+/// Build messages count columns in bytes, so they're converted to UTF-16 code units using the
+/// SDL in `subgraphs`.
+pub fn to_sarif(
+    build_messages: &[BuildMessage],
+    subgraphs: &[SubgraphDefinition],
+    subgraph_files: &BTreeMap<String, PathBuf>,
+    config_dir: &Path,
+) -> Value {
+    let mut rules: Vec<&str> = Vec::new();
+    let results: Vec<Value> = build_messages
+        .iter()
+        .map(|message| {
+            let schema_coordinate = message.schema_coordinate.as_deref();
+            let locations: Vec<Value> = message
+                .locations
+                .iter()
+                .map(|location| {
+                    sarif_location(
+                        location,
+                        schema_coordinate,
+                        subgraphs,
+                        subgraph_files,
+                        config_dir,
+                    )
+                })
+                .collect();
+            let mut result = json!({
+                "level": level(message.level),
+                "message": { "text": message.message },
+                "locations": locations,
+            });
+            if let Some(code) = message.code.as_deref() {
+                let index = rules
+                    .iter()
+                    .position(|rule| *rule == code)
+                    .unwrap_or_else(|| {
+                        rules.push(code);
+                        rules.len() - 1
+                    });
+                result["ruleId"] = json!(code);
+                result["ruleIndex"] = json!(index);
+            }
+            result
+        })
+        .collect();
+
+    let config_dir = std::path::absolute(config_dir).unwrap_or_else(|_| config_dir.to_path_buf());
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": TOOL_NAME,
+                    "informationUri": TOOL_INFORMATION_URI,
+                    "rules": rules.iter().map(|rule| json!({ "id": rule })).collect::<Vec<_>>(),
+                }
+            },
+            "originalUriBaseIds": {
+                CONFIG_DIR_BASE_ID: { "uri": format!("{}/", file_uri(&config_dir).trim_end_matches('/')) },
+            },
+            "columnKind": "utf16CodeUnits",
+            "results": results,
+        }]
+    })
+}
+
+impl PluginResult {
+    /// Converts the build messages to SARIF, see [`to_sarif`].
+    pub fn to_sarif(
+        &self,
+        subgraphs: &[SubgraphDefinition],
+        subgraph_files: &BTreeMap<String, PathBuf>,
+        config_dir: &Path,
+    ) -> Value {
+        to_sarif(&self.build_messages, subgraphs, subgraph_files, config_dir)
+    }
+}
+
+/// Converts composition issues to SARIF, see [`to_sarif`].
+#[cfg(feature = "composition")]
+pub fn issues_to_sarif(
+    issues: impl IntoIterator<Item = crate::composition::Issue>,
+    subgraphs: &[SubgraphDefinition],
+    subgraph_files: &BTreeMap<String, PathBuf>,
+    config_dir: &Path,
+) -> Value {
+    let build_messages: Vec<BuildMessage> = issues
+        .into_iter()
+        .map(|issue| issue.into_build_message_with_sdl(subgraphs))
+        .collect();
+    to_sarif(&build_messages, subgraphs, subgraph_files, config_dir)
+}
+
+fn level(level: BuildMessageLevel) -> &'static str {
+    match level {
+        BuildMessageLevel::Error => "error",
+        BuildMessageLevel::Warn => "warning",
+        BuildMessageLevel::Debug | BuildMessageLevel::Info => "note",
+    }
+}
+
+fn sarif_location(
+    location: &BuildMessageLocation,
+    schema_coordinate: Option<&str>,
+    subgraphs: &[SubgraphDefinition],
+    subgraph_files: &BTreeMap<String, PathBuf>,
+    config_dir: &Path,
+) -> Value {
+    let mut logical_locations = Vec::new();
+    if let Some(subgraph) = &location.subgraph {
+        logical_locations.push(json!({ "name": subgraph, "kind": "module" }));
+    }
+    if let Some(schema_coordinate) = schema_coordinate {
+        logical_locations
+            .push(json!({ "fullyQualifiedName": schema_coordinate, "kind": "member" }));
+    }
+    let mut sarif_location = json!({ "logicalLocations": logical_locations });
+
+    let start = location
+        .start
+        .as_ref()
+        .and_then(|start| Some((start.line?, start.column?)));
+    let end = location
+        .end
+        .as_ref()
+        .and_then(|end| Some((end.line?, end.column?)));
+    let file_range = location
+        .source
+        .as_ref()
+        .zip(location.other.get(FILE_RANGE_KEY))
+        .and_then(|(source, range)| Some((PathBuf::from(source), file_range(range)?)));
+    let (file, start, end) = match file_range {
+        Some((file, (file_start, file_end))) => (file, Some(file_start), Some(file_end)),
+        None => {
+            let Some(file) = location
+                .subgraph
+                .as_ref()
+                .and_then(|subgraph| subgraph_files.get(subgraph))
+            else {
+                return sarif_location;
+            };
+            (file.clone(), start, end)
+        }
+    };
+    // Lines in a file are offset from the same lines in the subgraph's SDL, if the SDL was
+    // concatenated from several files.
+    let line_offset = location
+        .start
+        .as_ref()
+        .and_then(|point| point.line)
+        .zip(start)
+        .map_or(0, |(sdl_line, (file_line, _))| {
+            sdl_line as isize - file_line as isize
+        });
+    let sdl = location.subgraph.as_deref().and_then(|name| {
+        subgraphs
+            .iter()
+            .find(|subgraph| subgraph.name == name)
+            .map(|subgraph| subgraph.sdl.as_str())
+    });
+    let column = |line: usize, column: usize| {
+        let text = sdl.and_then(|sdl| {
+            let sdl_line = line.checked_add_signed(line_offset)?;
+            sdl.lines().nth(sdl_line.checked_sub(1)?)
+        });
+        utf16_column(text, column)
+    };
+
+    let mut physical_location = json!({ "artifactLocation": artifact_location(&file, config_dir) });
+    if let Some((start_line, start_column)) = start {
+        let mut region = json!({
+            "startLine": start_line,
+            "startColumn": column(start_line, start_column),
+        });
+        if let Some((end_line, end_column)) = end {
+            region["endLine"] = json!(end_line);
+            region["endColumn"] = json!(column(end_line, end_column));
+        }
+        physical_location["region"] = region;
+    }
+    sarif_location["physicalLocation"] = physical_location;
+    sarif_location
+}
+
+/// The start and end line and column of a `fileRange`.
+fn file_range(range: &Value) -> Option<((usize, usize), (usize, usize))> {
+    let point = |point: &Value| {
+        Some((
+            usize::try_from(point["line"].as_u64()?).ok()?,
+            usize::try_from(point["column"].as_u64()?).ok()?,
+        ))
+    };
+    Some((point(&range["start"])?, point(&range["end"])?))
+}
+
+/// Converts a one-based column counting bytes in `text` to one counting UTF-16 code units.
+/// Without the text, the column is assumed to be ASCII.
+fn utf16_column(text: Option<&str>, column: usize) -> usize {
+    let Some(text) = text.map(|text| text.trim_end_matches('\r')) else {
+        return column;
+    };
+    let bytes = column.saturating_sub(1);
+    let mut end = bytes.min(text.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    // Columns past the end of the line count as one unit each.
+    text[..end].chars().map(char::len_utf16).sum::<usize>() + bytes.saturating_sub(text.len()) + 1
+}
+
+/// Files in the config directory are relative to its base URI, others are absolute `file` URIs.
+fn artifact_location(file: &Path, config_dir: &Path) -> Value {
+    match file.strip_prefix(config_dir) {
+        Ok(relative) => json!({ "uri": uri(relative), "uriBaseId": CONFIG_DIR_BASE_ID }),
+        Err(_) if file.is_relative() => {
+            json!({ "uri": uri(file), "uriBaseId": CONFIG_DIR_BASE_ID })
+        }
+        Err(_) => json!({ "uri": file_uri(file) }),
+    }
+}
+
+/// SARIF wants URI references, which always use forward slashes.
+fn uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/:".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    uri
+}
+
+fn file_uri(path: &Path) -> String {
+    let path = uri(path);
+    if path.starts_with('/') {
+        format!("file://{path}")
+    } else {
+        // Windows paths start with a drive letter
+        format!("file:///{path}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build_plugin::BuildMessagePoint;
+
+    fn point(line: usize, column: usize) -> Option<BuildMessagePoint> {
+        Some(BuildMessagePoint {
+            start: None,
+            end: None,
+            line: Some(line),
+            column: Some(column),
+        })
+    }
+
+    #[test]
+    fn it_converts_build_messages() {
+        let mut error = BuildMessage::new_error(
+            "[products] Non-shareable field".to_string(),
+            None,
+            Some("INVALID_FIELD_SHARING".to_string()),
+        );
+        error.schema_coordinate = Some("Product.name".to_string());
+        error.locations = vec![
+            BuildMessageLocation {
+                subgraph: Some("products".to_string()),
+                start: point(6, 3),
+                end: point(6, 15),
+                ..Default::default()
+            },
+            BuildMessageLocation {
+                subgraph: Some("reviews".to_string()),
+                ..Default::default()
+            },
+        ];
+        let mut hint = BuildMessage::new_error(
+            "Something to look at".to_string(),
+            None,
+            Some("SOME_HINT".to_string()),
+        );
+        hint.level = BuildMessageLevel::Warn;
+        let result = PluginResult::new(Ok("".to_string()), vec![error.clone(), hint, error]);
+        let files = BTreeMap::from([("products".to_string(), PathBuf::from("products.graphql"))]);
+
+        let sarif = result.to_sarif(&[], &files, Path::new("/configs"));
+        let run = &sarif["runs"][0];
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(
+            run["originalUriBaseIds"],
+            json!({ "CONFIG_DIR": { "uri": "file:///configs/" } })
+        );
+        assert_eq!(
+            run["tool"]["driver"]["rules"],
+            json!([{ "id": "INVALID_FIELD_SHARING" }, { "id": "SOME_HINT" }])
+        );
+        assert_eq!(run["results"][1]["level"], "warning");
+        assert_eq!(run["results"][1]["ruleIndex"], 1);
+        assert_eq!(run["results"][2]["ruleIndex"], 0);
+        assert_eq!(
+            run["results"][0],
+            json!({
+                "ruleId": "INVALID_FIELD_SHARING",
+                "ruleIndex": 0,
+                "level": "error",
+                "message": { "text": "[products] Non-shareable field" },
+                "locations": [
+                    {
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": "products.graphql", "uriBaseId": "CONFIG_DIR" },
+                            "region": { "startLine": 6, "startColumn": 3, "endLine": 6, "endColumn": 15 },
+                        },
+                        "logicalLocations": [
+                            { "name": "products", "kind": "module" },
+                            { "fullyQualifiedName": "Product.name", "kind": "member" },
+                        ],
+                    },
+                    {
+                        "logicalLocations": [
+                            { "name": "reviews", "kind": "module" },
+                            { "fullyQualifiedName": "Product.name", "kind": "member" },
+                        ],
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn it_converts_columns_to_utf16_code_units() {
+        let subgraphs = vec![SubgraphDefinition {
+            name: "products".to_string(),
+            url: "http://products".to_string(),
+            sdl: "type Query {\n  \"é😀\" a: Int b: Int\n}\n".to_string(),
+        }];
+        let mut error =
+            BuildMessage::new_error("message".to_string(), None, Some("CODE".to_string()));
+        // `b: Int` is at bytes 18..24 of the line
+        error.locations = vec![BuildMessageLocation {
+            subgraph: Some("products".to_string()),
+            start: point(2, 19),
+            end: point(2, 25),
+            ..Default::default()
+        }];
+        let files = BTreeMap::from([(
+            "products".to_string(),
+            PathBuf::from("/elsewhere/my schema.graphql"),
+        )]);
+
+        let sarif = to_sarif(&[error], &subgraphs, &files, Path::new("/configs"));
+        assert_eq!(
+            sarif["runs"][0]["results"][0]["locations"][0]["physicalLocation"],
+            json!({
+                "artifactLocation": { "uri": "file:///elsewhere/my%20schema.graphql" },
+                "region": { "startLine": 2, "startColumn": 16, "endLine": 2, "endColumn": 22 },
+            })
+        );
+    }
+
+    #[test]
+    fn it_locates_messages_in_the_files_subgraphs_were_concatenated_from() {
+        let subgraphs = vec![SubgraphDefinition {
+            name: "products".to_string(),
+            url: "http://products".to_string(),
+            sdl: "type Query {\n  a: A\n}\ntype A {\n  \"😀\" b: Int\n}\n".to_string(),
+        }];
+        let mut error =
+            BuildMessage::new_error("message".to_string(), None, Some("CODE".to_string()));
+        let mut location = BuildMessageLocation {
+            subgraph: Some("products".to_string()),
+            source: Some("/configs/schemas/b.graphql".to_string()),
+            start: point(5, 10),
+            end: point(5, 16),
+            ..Default::default()
+        };
+        location.other.insert(
+            FILE_RANGE_KEY.to_string(),
+            json!({
+                "start": { "line": 2, "column": 10 },
+                "end": { "line": 2, "column": 16 },
+            }),
+        );
+        error.locations = vec![location];
+
+        let sarif = to_sarif(
+            &[error],
+            &subgraphs,
+            &BTreeMap::new(),
+            Path::new("/configs"),
+        );
+        assert_eq!(
+            sarif["runs"][0]["results"][0]["locations"][0]["physicalLocation"],
+            json!({
+                "artifactLocation": { "uri": "schemas/b.graphql", "uriBaseId": "CONFIG_DIR" },
+                "region": { "startLine": 2, "startColumn": 8, "endLine": 2, "endColumn": 14 },
+            })
+        );
+    }
+}
@@ -0,0 +1,473 @@
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use apollo_federation::error::ErrorCode;
+use strum::IntoEnumIterator;
+
+use super::Severity;
+
+const ERRORS_DOCS_URL: &str =
+    "https://www.apollographql.com/docs/graphos/reference/federation/errors";
+const HINTS_DOCS_URL: &str =
+    "https://www.apollographql.com/docs/graphos/reference/federation/hints";
+
+/// A known [`Issue::code`](super::Issue::code), as listed by [`issue_codes`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IssueCode {
+    pub code: String,
+    /// The severity issues with this code have unless composition says otherwise.
+    pub default_severity: Severity,
+    /// A one-line explanation of what this code means.
+    pub explanation: String,
+    /// The documentation of this code, or of its severity for codes which aren't documented on
+    /// their own.
+    pub docs_url: String,
+}
+
+impl IssueCode {
+    /// A code documented at its own anchor on the errors or hints page.
+    fn new(
+        code: impl Into<String>,
+        default_severity: Severity,
+        explanation: impl Into<String>,
+    ) -> Self {
+        let code = code.into();
+        Self {
+            docs_url: format!(
+                "{}#{}",
+                default_docs_url(default_severity),
+                code.to_ascii_lowercase()
+            ),
+            code,
+            default_severity,
+            explanation: explanation.into(),
+        }
+    }
+
+    /// A code of this crate or `apollo-composition`, which isn't in the `apollo-federation` docs.
+    fn local(
+        code: impl Into<String>,
+        default_severity: Severity,
+        explanation: impl Into<String>,
+    ) -> Self {
+        Self {
+            code: code.into(),
+            docs_url: default_docs_url(default_severity).to_string(),
+            default_severity,
+            explanation: explanation.into(),
+        }
+    }
+}
+
+/// A bug in composition, including a panic in a phase of `apollo-composition`.
+pub const INTERNAL_ERROR: &str = "INTERNAL_ERROR";
+/// An error which was reported without a code.
+pub const UNKNOWN_ERROR_CODE: &str = "UNKNOWN_ERROR_CODE";
+/// A hint which was reported without a code.
+pub const UNKNOWN_HINT_CODE: &str = "UNKNOWN_HINT_CODE";
+/// `@override` of a field in a subgraph with connectors.
+pub const OVERRIDE_ON_CONNECTOR: &str = "OVERRIDE_ON_CONNECTOR";
+/// More subgraphs than `CompositionLimits::max_subgraphs` allows.
+pub const MAX_SUBGRAPHS_EXCEEDED: &str = "MAX_SUBGRAPHS_EXCEEDED";
+/// A subgraph's SDL is larger than `CompositionLimits::max_subgraph_sdl_bytes` allows.
+pub const MAX_SUBGRAPH_SDL_BYTES_EXCEEDED: &str = "MAX_SUBGRAPH_SDL_BYTES_EXCEEDED";
+/// A subgraph defines more types than `CompositionLimits::max_subgraph_types` allows.
+pub const MAX_SUBGRAPH_TYPES_EXCEEDED: &str = "MAX_SUBGRAPH_TYPES_EXCEEDED";
+/// A subgraph defines more fields than `CompositionLimits::max_subgraph_fields` allows.
+pub const MAX_SUBGRAPH_FIELDS_EXCEEDED: &str = "MAX_SUBGRAPH_FIELDS_EXCEEDED";
+
+/// Codes produced by this crate and `apollo-composition` rather than by `apollo-federation`.
+const LOCAL_CODES: &[(&str, Severity, &str)] = &[
+    (
+        INTERNAL_ERROR,
+        Severity::Error,
+        "Composition failed due to a bug, which should be reported.",
+    ),
+    (
+        UNKNOWN_ERROR_CODE,
+        Severity::Error,
+        "An error was reported without a code.",
+    ),
+    (
+        UNKNOWN_HINT_CODE,
+        Severity::Warning,
+        "A hint was reported without a code.",
+    ),
+    (
+        OVERRIDE_ON_CONNECTOR,
+        Severity::Error,
+        "A field uses @override to take over a field from a subgraph with connectors, which isn't supported yet.",
+    ),
+    (
+        MAX_SUBGRAPHS_EXCEEDED,
+        Severity::Error,
+        "There are more subgraphs than the composition limits allow.",
+    ),
+    (
+        MAX_SUBGRAPH_SDL_BYTES_EXCEEDED,
+        Severity::Error,
+        "A subgraph's SDL is larger than the composition limits allow.",
+    ),
+    (
+        MAX_SUBGRAPH_TYPES_EXCEEDED,
+        Severity::Error,
+        "A subgraph defines more types than the composition limits allow.",
+    ),
+    (
+        MAX_SUBGRAPH_FIELDS_EXCEEDED,
+        Severity::Error,
+        "A subgraph defines more fields than the composition limits allow.",
+    ),
+];
+
+/// Codes of [`ConfigError`](crate::config::ConfigError)s, which are reported like composition
+/// errors.
+#[cfg(feature = "config")]
+const CONFIG_CODES: &[(&str, Severity, &str)] = {
+    use crate::config::codes::*;
+    &[
+        (
+            CONFIG_INVALID,
+            Severity::Error,
+            "The supergraph config couldn't be parsed.",
+        ),
+        (
+            CONFIG_FILE_MISSING,
+            Severity::Error,
+            "A file the supergraph config refers to doesn't exist.",
+        ),
+        (
+            CONFIG_EXTENDS_CYCLE,
+            Severity::Error,
+            "Supergraph configs extend each other in a cycle.",
+        ),
+        (
+            CONFIG_MERGE_CONFLICT,
+            Severity::Error,
+            "Merged supergraph configs set the same key differently.",
+        ),
+        (
+            CONFIG_ENV_VAR_MISSING,
+            Severity::Error,
+            "An environment variable used in the supergraph config isn't set and has no default.",
+        ),
+        (
+            SUBGRAPHS_NOT_RESOLVED,
+            Severity::Error,
+            "The schemas of some subgraphs couldn't be resolved.",
+        ),
+        (
+            NO_SUBGRAPHS_FOUND,
+            Severity::Error,
+            "The supergraph config has no subgraphs.",
+        ),
+        (
+            SUBGRAPH_SCHEMA_NOT_RESOLVED,
+            Severity::Error,
+            "A subgraph's schema couldn't be resolved.",
+        ),
+        (
+            SUBGRAPH_COMMAND_FAILED,
+            Severity::Error,
+            "The command printing a subgraph's schema failed, timed out or printed too much.",
+        ),
+    ]
+};
+#[cfg(not(feature = "config"))]
+const CONFIG_CODES: &[(&str, Severity, &str)] = &[];
+
+/// Hint codes of `apollo-federation`, which doesn't expose a way to list them.
+const HINT_CODES: &[(&str, &str)] = &[
+    (
+        "INCONSISTENT_BUT_COMPATIBLE_FIELD_TYPE",
+        "Field has inconsistent but compatible type across subgraphs",
+    ),
+    (
+        "INCONSISTENT_BUT_COMPATIBLE_ARGUMENT_TYPE",
+        "Argument has inconsistent but compatible type across subgraphs",
+    ),
+    (
+        "INCONSISTENT_DEFAULT_VALUE_PRESENCE",
+        "Default value presence is inconsistent across subgraphs",
+    ),
+    (
+        "INCONSISTENT_ENTITY",
+        "Entity definition is inconsistent across subgraphs",
+    ),
+    (
+        "INCONSISTENT_OBJECT_VALUE_TYPE_FIELD",
+        "Object value type field is inconsistent across subgraphs",
+    ),
+    (
+        "INCONSISTENT_INTERFACE_VALUE_TYPE_FIELD",
+        "Interface value type field is inconsistent across subgraphs",
+    ),
+    (
+        "INCONSISTENT_INPUT_OBJECT_FIELD",
+        "Input object field is inconsistent across subgraphs",
+    ),
+    (
+        "INCONSISTENT_UNION_MEMBER",
+        "Union member is inconsistent across subgraphs",
+    ),
+    (
+        "INCONSISTENT_ENUM_VALUE_FOR_INPUT_ENUM",
+        "Enum value for input enum is inconsistent across subgraphs",
+    ),
+    (
+        "INCONSISTENT_ENUM_VALUE_FOR_OUTPUT_ENUM",
+        "Enum value for output enum is inconsistent across subgraphs",
+    ),
+    (
+        "INCONSISTENT_TYPE_SYSTEM_DIRECTIVE_REPEATABLE",
+        "Type system directive repeatable property is inconsistent across subgraphs",
+    ),
+    (
+        "INCONSISTENT_TYPE_SYSTEM_DIRECTIVE_LOCATIONS",
+        "Type system directive locations are inconsistent across subgraphs",
+    ),
+    (
+        "INCONSISTENT_EXECUTABLE_DIRECTIVE_PRESENCE",
+        "Executable directive presence is inconsistent across subgraphs",
+    ),
+    (
+        "NO_EXECUTABLE_DIRECTIVE_LOCATIONS_INTERSECTION",
+        "No intersection between executable directive locations across subgraphs",
+    ),
+    (
+        "INCONSISTENT_EXECUTABLE_DIRECTIVE_REPEATABLE",
+        "Executable directive repeatable property is inconsistent across subgraphs",
+    ),
+    (
+        "INCONSISTENT_EXECUTABLE_DIRECTIVE_LOCATIONS",
+        "Executable directive locations are inconsistent across subgraphs",
+    ),
+    (
+        "INCONSISTENT_DESCRIPTION",
+        "Description is inconsistent across subgraphs",
+    ),
+    (
+        "INCONSISTENT_ARGUMENT_PRESENCE",
+        "Argument presence is inconsistent across subgraphs",
+    ),
+    (
+        "FROM_SUBGRAPH_DOES_NOT_EXIST",
+        "From subgraph does not exist",
+    ),
+    (
+        "OVERRIDDEN_FIELD_CAN_BE_REMOVED",
+        "Overridden field can be removed",
+    ),
+    (
+        "OVERRIDE_DIRECTIVE_CAN_BE_REMOVED",
+        "Override directive can be removed",
+    ),
+    (
+        "OVERRIDE_MIGRATION_IN_PROGRESS",
+        "Override migration is in progress",
+    ),
+    ("UNUSED_ENUM_TYPE", "Enum type is unused"),
+    (
+        "INCONSISTENT_NON_REPEATABLE_DIRECTIVE_ARGUMENTS",
+        "Non-repeatable directive arguments are inconsistent across subgraphs",
+    ),
+    (
+        "MERGED_NON_REPEATABLE_DIRECTIVE_ARGUMENTS",
+        "Non-repeatable directive arguments have been merged",
+    ),
+    (
+        "DIRECTIVE_COMPOSITION_INFO",
+        "Directive composition information",
+    ),
+    (
+        "DIRECTIVE_COMPOSITION_WARN",
+        "Directive composition warning",
+    ),
+    (
+        "INCONSISTENT_RUNTIME_TYPES_FOR_SHAREABLE_RETURN",
+        "Runtime types for shareable return are inconsistent across subgraphs",
+    ),
+    (
+        "IMPLICITLY_UPGRADED_FEDERATION_VERSION",
+        "Federation version has been implicitly upgraded",
+    ),
+    (
+        "CONTEXTUAL_ARGUMENT_NOT_CONTEXTUAL_IN_ALL_SUBGRAPHS",
+        "Contextual argument is not contextual in all subgraphs",
+    ),
+    (
+        "INTERFACE_KEY_MISSING_IMPLEMENTATION_TYPE",
+        "Interface key missing implementation type",
+    ),
+];
+
+static ISSUE_CODES: LazyLock<Vec<IssueCode>> = LazyLock::new(|| {
+    let error_codes = ErrorCode::iter().map(|code| {
+        let definition = code.definition();
+        IssueCode::new(
+            definition.code(),
+            Severity::Error,
+            definition.doc_description(),
+        )
+    });
+    let hint_codes = HINT_CODES
+        .iter()
+        .map(|(code, explanation)| IssueCode::new(*code, Severity::Warning, *explanation));
+    let local_codes = LOCAL_CODES
+        .iter()
+        .chain(CONFIG_CODES)
+        .map(|(code, severity, explanation)| IssueCode::local(*code, *severity, *explanation));
+
+    let mut seen = HashSet::new();
+    error_codes
+        .chain(hint_codes)
+        .chain(local_codes)
+        .filter(|issue_code| seen.insert(issue_code.code.clone()))
+        .collect()
+});
+
+/// Every issue code composition is known to produce, including codes from `apollo-federation`.
+///
+/// Connectors validation codes aren't included, since `apollo-federation` doesn't expose a way to
+/// list them.
+pub fn issue_codes() -> &'static [IssueCode] {
+    &ISSUE_CODES
+}
+
+/// Looks up a known issue code, returning `None` for unknown codes.
+pub fn explain(code: &str) -> Option<&'static IssueCode> {
+    ISSUE_CODES
+        .iter()
+        .find(|issue_code| issue_code.code == code)
+}
+
+/// The documentation for an issue code, falling back to the general page for its severity if the
+/// code is unknown.
+pub(super) fn docs_url(code: &str, severity: Severity) -> &'static str {
+    explain(code).map_or(default_docs_url(severity), |issue_code| {
+        issue_code.docs_url.as_str()
+    })
+}
+
+fn default_docs_url(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => ERRORS_DOCS_URL,
+        Severity::Warning => HINTS_DOCS_URL,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apollo_federation::composition::{compose, CompositionOptions};
+    use apollo_federation::subgraph::typestate::Subgraph;
+
+    use super::*;
+
+    #[test]
+    fn it_explains_known_codes() {
+        let field_sharing = explain("INVALID_FIELD_SHARING").unwrap();
+        assert_eq!(field_sharing.default_severity, Severity::Error);
+        assert!(!field_sharing.explanation.is_empty());
+        assert_eq!(
+            field_sharing.docs_url,
+            format!("{ERRORS_DOCS_URL}#invalid_field_sharing")
+        );
+
+        let hint = explain("INCONSISTENT_DESCRIPTION").unwrap();
+        assert_eq!(hint.default_severity, Severity::Warning);
+        assert_eq!(
+            hint.docs_url,
+            format!("{HINTS_DOCS_URL}#inconsistent_description")
+        );
+
+        let limit = explain(MAX_SUBGRAPHS_EXCEEDED).unwrap();
+        assert_eq!(limit.default_severity, Severity::Error);
+        assert_eq!(limit.docs_url, ERRORS_DOCS_URL);
+        assert_eq!(explain("NOT_A_REAL_CODE"), None);
+    }
+
+    #[test]
+    fn it_registers_every_local_code() {
+        let mut codes = vec![
+            INTERNAL_ERROR,
+            UNKNOWN_ERROR_CODE,
+            UNKNOWN_HINT_CODE,
+            OVERRIDE_ON_CONNECTOR,
+            MAX_SUBGRAPHS_EXCEEDED,
+            MAX_SUBGRAPH_SDL_BYTES_EXCEEDED,
+            MAX_SUBGRAPH_TYPES_EXCEEDED,
+            MAX_SUBGRAPH_FIELDS_EXCEEDED,
+        ];
+        #[cfg(feature = "config")]
+        {
+            use crate::config::codes::*;
+            codes.extend([
+                CONFIG_INVALID,
+                CONFIG_FILE_MISSING,
+                CONFIG_EXTENDS_CYCLE,
+                CONFIG_MERGE_CONFLICT,
+                CONFIG_ENV_VAR_MISSING,
+                SUBGRAPHS_NOT_RESOLVED,
+                NO_SUBGRAPHS_FOUND,
+                SUBGRAPH_SCHEMA_NOT_RESOLVED,
+                SUBGRAPH_COMMAND_FAILED,
+            ]);
+        }
+        for code in codes {
+            let issue_code = explain(code).unwrap_or_else(|| panic!("{code} isn't registered"));
+            assert!(!issue_code.explanation.is_empty());
+        }
+    }
+
+    #[test]
+    fn it_lists_each_code_once() {
+        let codes: HashSet<&str> = issue_codes()
+            .iter()
+            .map(|issue_code| issue_code.code.as_str())
+            .collect();
+        assert_eq!(codes.len(), issue_codes().len());
+    }
+
+    #[test]
+    fn it_knows_the_hint_codes_composition_emits() {
+        let link = r#"extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@shareable"])"#;
+        let subgraphs = vec![
+            Subgraph::parse(
+                "products",
+                "http://products",
+                &format!(
+                    r#"{link}
+                    type Query {{ products: [Product] }}
+                    "A product"
+                    type Product @key(fields: "id") {{ id: ID! name: String @shareable }}
+                    enum Unused {{ A }}
+                    input Filter {{ name: String limit: Int }}
+                    "#
+                ),
+            )
+            .unwrap(),
+            Subgraph::parse(
+                "reviews",
+                "http://reviews",
+                &format!(
+                    r#"{link}
+                    "Something to review"
+                    type Product @key(fields: "id") {{ id: ID! name: String! @shareable }}
+                    input Filter {{ name: String }}
+                    "#
+                ),
+            )
+            .unwrap(),
+        ];
+        let supergraph = compose(subgraphs, CompositionOptions::default()).unwrap();
+        let codes: Vec<&str> = supergraph.hints().iter().map(|hint| hint.code()).collect();
+
+        assert!(!codes.is_empty());
+        for code in codes {
+            assert!(
+                HINT_CODES.iter().any(|(hint_code, _)| *hint_code == code),
+                "{code} is missing from HINT_CODES"
+            );
+        }
+    }
+}
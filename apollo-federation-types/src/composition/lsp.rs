@@ -0,0 +1,290 @@
+//! Conversion of [`Issue`]s to Language Server Protocol diagnostics, for editor integrations.
+
+use std::collections::BTreeMap;
+
+use apollo_compiler::parser::LineColumn;
+use serde::{Deserialize, Serialize};
+
+use super::{codes, Issue, Severity, SubgraphLocation};
+use crate::javascript::SubgraphDefinition;
+
+const SOURCE: &str = "apollo-federation";
+
+/// A diagnostic as defined by the
+/// [Language Server Protocol](https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#diagnostic),
+/// which serializes to the JSON LSP clients expect.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    pub range: Range,
+    pub severity: DiagnosticSeverity,
+    pub code: String,
+    pub code_description: CodeDescription,
+    pub source: String,
+    pub message: String,
+    /// The issue's locations other than [`Diagnostic::range`], which may be in other subgraphs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related_information: Vec<DiagnosticRelatedInformation>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// A zero-based position, where `character` counts UTF-16 code units.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    pub line: u32,
+    pub character: u32,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(into = "u8", try_from = "u8")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+    Information,
+    Hint,
+}
+
+impl From<DiagnosticSeverity> for u8 {
+    fn from(severity: DiagnosticSeverity) -> Self {
+        match severity {
+            DiagnosticSeverity::Error => 1,
+            DiagnosticSeverity::Warning => 2,
+            DiagnosticSeverity::Information => 3,
+            DiagnosticSeverity::Hint => 4,
+        }
+    }
+}
+
+impl TryFrom<u8> for DiagnosticSeverity {
+    type Error = String;
+
+    fn try_from(severity: u8) -> Result<Self, String> {
+        match severity {
+            1 => Ok(DiagnosticSeverity::Error),
+            2 => Ok(DiagnosticSeverity::Warning),
+            3 => Ok(DiagnosticSeverity::Information),
+            4 => Ok(DiagnosticSeverity::Hint),
+            _ => Err(format!("invalid diagnostic severity {severity}")),
+        }
+    }
+}
+
+impl From<Severity> for DiagnosticSeverity {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Error => DiagnosticSeverity::Error,
+            Severity::Warning => DiagnosticSeverity::Warning,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CodeDescription {
+    pub href: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DiagnosticRelatedInformation {
+    pub location: Location,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    pub uri: String,
+    pub range: Range,
+}
+
+/// Converts issues to LSP diagnostics, grouped by the URI of the subgraph document they belong
+/// in, ready to be sent as `textDocument/publishDiagnostics` notifications.
+///
+/// An issue becomes one diagnostic in each subgraph it has a location in, with its other
+/// locations as related information. Issues without any located subgraph can't be shown in a
+/// document, so they're left out.
+///
+/// `subgraph_uri` returns the URI of the document for a subgraph name. The subgraphs' SDL is
+/// needed to convert columns to UTF-16; locations in subgraphs which aren't in `subgraphs` are
+/// converted as if their SDL was ASCII.
+pub fn lsp_diagnostics<'a>(
+    issues: impl IntoIterator<Item = &'a Issue>,
+    subgraphs: &[SubgraphDefinition],
+    subgraph_uri: impl Fn(&str) -> String,
+) -> BTreeMap<String, Vec<Diagnostic>> {
+    let sources: BTreeMap<&str, &str> = subgraphs
+        .iter()
+        .map(|subgraph| (subgraph.name.as_str(), subgraph.sdl.as_str()))
+        .collect();
+    let lsp_location = |location: &SubgraphLocation| -> Option<Location> {
+        let subgraph = location.subgraph.as_deref()?;
+        let range = location.range.as_ref()?;
+        let source = sources.get(subgraph).copied().unwrap_or_default();
+        Some(Location {
+            uri: subgraph_uri(subgraph),
+            range: Range {
+                start: position(source, range.start),
+                end: position(source, range.end),
+            },
+        })
+    };
+
+    let mut diagnostics: BTreeMap<String, Vec<Diagnostic>> = BTreeMap::new();
+    for issue in issues {
+        let locations: Vec<Location> = issue.locations.iter().filter_map(lsp_location).collect();
+        let docs_url = codes::docs_url(&issue.code, issue.severity);
+        for (index, location) in locations.iter().enumerate() {
+            // Only the first location in each document becomes a diagnostic
+            if locations[..index]
+                .iter()
+                .any(|previous| previous.uri == location.uri)
+            {
+                continue;
+            }
+            diagnostics
+                .entry(location.uri.clone())
+                .or_default()
+                .push(Diagnostic {
+                    range: location.range,
+                    severity: issue.severity.into(),
+                    code: issue.code.clone(),
+                    code_description: CodeDescription {
+                        href: docs_url.to_string(),
+                    },
+                    source: SOURCE.to_string(),
+                    message: issue.message.clone(),
+                    related_information: locations
+                        .iter()
+                        .enumerate()
+                        .filter(|(other, _)| *other != index)
+                        .map(|(_, other)| DiagnosticRelatedInformation {
+                            location: other.clone(),
+                            message: format!("{} is also reported here", issue.code),
+                        })
+                        .collect(),
+                });
+        }
+    }
+    diagnostics
+}
+
+/// Converts a one-based line and column counting bytes, as apollo-compiler reports them, to a
+/// zero-based LSP position counting UTF-16 code units.
+fn position(source: &str, line_column: LineColumn) -> Position {
+    let line = line_column.line.saturating_sub(1);
+    let bytes = line_column.column.saturating_sub(1);
+    let character = match source.lines().nth(line) {
+        Some(text) => {
+            let mut end = bytes.min(text.len());
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            let prefix: usize = text[..end].chars().map(char::len_utf16).sum();
+            // Columns past the end of the line (like the end of a range which includes the line
+            // break) count as one unit each.
+            prefix + bytes.saturating_sub(text.len())
+        }
+        None => bytes,
+    };
+    Position {
+        line: u32::try_from(line).unwrap_or(u32::MAX),
+        character: u32::try_from(character).unwrap_or(u32::MAX),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::offsets::parsed_field_range;
+    use super::*;
+
+    #[test]
+    fn it_counts_utf16_code_units() {
+        let source = "type Query {\n  \"é😀\" a: Int b: Int\n}";
+        let range = parsed_field_range(source, "b");
+        assert_eq!(
+            (position(source, range.start), position(source, range.end)),
+            (
+                Position {
+                    line: 1,
+                    character: 15
+                },
+                Position {
+                    line: 1,
+                    character: 21
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn it_groups_diagnostics_by_document() {
+        let subgraphs = vec![SubgraphDefinition {
+            name: "products".to_string(),
+            url: "http://products".to_string(),
+            sdl: "# 🚀\ntype Product { \"🚀\" id: ID name: String }".to_string(),
+        }];
+        let issue = Issue {
+            code: "INVALID_FIELD_SHARING".to_string(),
+            message: "Non-shareable field".to_string(),
+            locations: vec![
+                SubgraphLocation {
+                    subgraph: Some("products".to_string()),
+                    range: Some(parsed_field_range(&subgraphs[0].sdl, "name")),
+                },
+                // Not in `subgraphs`, so converted as if it was ASCII
+                SubgraphLocation {
+                    subgraph: Some("reviews".to_string()),
+                    range: Some(parsed_field_range("type Product { name: String }", "name")),
+                },
+                SubgraphLocation {
+                    subgraph: None,
+                    range: None,
+                },
+            ],
+            severity: Severity::Error,
+            satisfiability_counterexample: None,
+            schema_coordinate: None,
+        };
+
+        let diagnostics = lsp_diagnostics([&issue], &subgraphs, |name| {
+            format!("file:///{name}.graphql")
+        });
+        assert_eq!(diagnostics.len(), 2);
+        let products = &diagnostics["file:///products.graphql"][0];
+        assert_eq!(
+            serde_json::to_value(products).unwrap(),
+            serde_json::json!({
+                "range": {
+                    "start": { "line": 1, "character": 27 },
+                    "end": { "line": 1, "character": 39 },
+                },
+                "severity": 1,
+                "code": "INVALID_FIELD_SHARING",
+                "codeDescription": {
+                    "href": "https://www.apollographql.com/docs/graphos/reference/federation/errors#invalid_field_sharing",
+                },
+                "source": "apollo-federation",
+                "message": "Non-shareable field",
+                "relatedInformation": [{
+                    "location": {
+                        "uri": "file:///reviews.graphql",
+                        "range": {
+                            "start": { "line": 0, "character": 15 },
+                            "end": { "line": 0, "character": 27 },
+                        },
+                    },
+                    "message": "INVALID_FIELD_SHARING is also reported here",
+                }],
+            })
+        );
+        assert_eq!(
+            diagnostics["file:///reviews.graphql"][0].related_information[0]
+                .location
+                .uri,
+            "file:///products.graphql"
+        );
+    }
+}
@@ -1,15 +1,40 @@
 //! Types used with the `apollo-composition` crate
 
+#[cfg(feature = "config")]
+use crate::build_plugin::FILE_RANGE_KEY;
 use crate::build_plugin::{
     BuildMessage, BuildMessageLevel, BuildMessageLocation, BuildMessagePoint,
 };
-use crate::javascript::{CompositionHint, GraphQLError, SubgraphASTNode};
+#[cfg(feature = "config")]
+use crate::config::SourceMap;
+use crate::javascript::{CompositionHint, GraphQLError, SubgraphASTNode, SubgraphDefinition};
 use crate::rover::{BuildError, BuildHint};
 use apollo_compiler::parser::LineColumn;
 use apollo_federation::error::{CompositionError, FederationError};
+use schema_coordinate::schema_coordinate_at;
 use std::collections::HashSet;
 use std::fmt::{Display, Formatter};
 use std::ops::Range;
+#[cfg(feature = "config")]
+use std::{collections::BTreeMap, path::Path};
+
+mod codes;
+pub mod lsp;
+mod offsets;
+mod render;
+mod satisfiability;
+mod schema_coordinate;
+
+pub use codes::{
+    explain, issue_codes, IssueCode, INTERNAL_ERROR, MAX_SUBGRAPHS_EXCEEDED,
+    MAX_SUBGRAPH_FIELDS_EXCEEDED, MAX_SUBGRAPH_SDL_BYTES_EXCEEDED, MAX_SUBGRAPH_TYPES_EXCEEDED,
+    OVERRIDE_ON_CONNECTOR, UNKNOWN_ERROR_CODE, UNKNOWN_HINT_CODE,
+};
+pub use render::IssueRenderer;
+pub use satisfiability::SatisfiabilityCounterexample;
+
+/// The key of [`Issue::satisfiability_counterexample`] in the `other` map of a [`BuildMessage`].
+const SATISFIABILITY_COUNTEREXAMPLE_KEY: &str = "satisfiabilityCounterexample";
 
 /// Group the types from the apollo-federation that can be ambiguous.
 mod native {
@@ -18,28 +43,38 @@ mod native {
 }
 
 /// Some issue the user should address. Errors block composition, warnings do not.
+///
+/// Create one with [`Issue::new`] and the `with_*` methods.
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
+#[non_exhaustive]
 pub struct Issue {
     pub code: String,
     pub message: String,
     pub locations: Vec<SubgraphLocation>,
     pub severity: Severity,
+    /// Only set for `SATISFIABILITY_ERROR`s.
+    pub satisfiability_counterexample: Option<Box<SatisfiabilityCounterexample>>,
+    /// The type, field, argument, or directive this issue is about, like
+    /// `Product.price(currency:)`, if there is one.
+    pub schema_coordinate: Option<String>,
 }
 
 impl Display for Issue {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        // TODO include subgraph error location information once available
         write!(f, "{}: {}", self.code, self.message)
     }
 }
 
 impl From<GraphQLError> for Issue {
     fn from(error: GraphQLError) -> Issue {
+        let code = error
+            .extensions
+            .map(|extension| extension.code)
+            .unwrap_or_default();
         Issue {
-            code: error
-                .extensions
-                .map(|extension| extension.code)
-                .unwrap_or_default(),
+            satisfiability_counterexample: satisfiability_counterexample(&code, &error.message),
+            schema_coordinate: None,
+            code,
             message: error.message,
             severity: Severity::Error,
             locations: error
@@ -56,6 +91,7 @@ impl From<CompositionHint> for Issue {
     fn from(hint: CompositionHint) -> Issue {
         Issue {
             code: hint.definition.code,
+            schema_coordinate: None,
             message: hint.message,
             severity: Severity::Warning,
             locations: hint
@@ -64,32 +100,140 @@ impl From<CompositionHint> for Issue {
                 .into_iter()
                 .filter_map(SubgraphLocation::from_ast)
                 .collect(),
+            satisfiability_counterexample: None,
         }
     }
 }
 
 impl From<BuildError> for Issue {
     fn from(error: BuildError) -> Issue {
+        Issue::from_build_error_with_sdl(error, &[])
+    }
+}
+
+impl Issue {
+    /// An issue without locations.
+    pub fn new(code: impl Into<String>, message: impl Into<String>, severity: Severity) -> Self {
+        Self {
+            code: code.into(),
+            message: message.into(),
+            locations: vec![],
+            severity,
+            satisfiability_counterexample: None,
+            schema_coordinate: None,
+        }
+    }
+
+    pub fn with_locations(mut self, locations: Vec<SubgraphLocation>) -> Self {
+        self.locations = locations;
+        self
+    }
+
+    pub fn with_satisfiability_counterexample(
+        mut self,
+        counterexample: SatisfiabilityCounterexample,
+    ) -> Self {
+        self.satisfiability_counterexample = Some(Box::new(counterexample));
+        self
+    }
+
+    pub fn with_schema_coordinate(mut self, schema_coordinate: impl Into<String>) -> Self {
+        self.schema_coordinate = Some(schema_coordinate.into());
+        self
+    }
+
+    /// The code and message, followed by each location. Use [`IssueRenderer`] to include the
+    /// offending SDL as well.
+    pub fn display_with_locations(&self) -> String {
+        let mut output = self.to_string();
+        for location in &self.locations {
+            output.push_str(&format!("\n  --> {location}"));
+        }
+        output
+    }
+
+    /// Sets the schema coordinate, if there isn't one yet, to the definition the first location
+    /// in `subgraphs` points at.
+    pub fn with_schema_coordinate_from_sdl(mut self, subgraphs: &[SubgraphDefinition]) -> Self {
+        if self.schema_coordinate.is_none() {
+            self.schema_coordinate = self.locations.iter().find_map(|location| {
+                let sdl = subgraph_sdl(subgraphs, location.subgraph.as_deref())?;
+                schema_coordinate_at(sdl, location.range.as_ref()?.start)
+            });
+        }
+        self
+    }
+
+    /// Like [`Issue::from`], but locations in `subgraphs` which only have byte offsets get their
+    /// line and column from the subgraph's SDL, and the schema coordinate is found from them.
+    pub fn from_build_error_with_sdl(error: BuildError, subgraphs: &[SubgraphDefinition]) -> Issue {
+        let code = error.code.unwrap_or_else(|| UNKNOWN_ERROR_CODE.to_string());
+        let message = error.message.unwrap_or_else(|| "Unknown error".to_string());
         Issue {
-            code: error
-                .code
-                .unwrap_or_else(|| "UNKNOWN_ERROR_CODE".to_string()),
-            message: error.message.unwrap_or_else(|| "Unknown error".to_string()),
+            satisfiability_counterexample: error
+                .other
+                .get(SATISFIABILITY_COUNTEREXAMPLE_KEY)
+                .and_then(|value| serde_json::from_value(value.clone()).ok())
+                .or_else(|| satisfiability_counterexample(&code, &message)),
+            schema_coordinate: None,
+            code,
+            message,
             locations: error
                 .nodes
                 .unwrap_or_default()
                 .into_iter()
-                .map(Into::into)
+                .map(|location| {
+                    let source = subgraph_sdl(subgraphs, location.subgraph.as_deref());
+                    SubgraphLocation::from_build_message_location(location, source)
+                })
                 .collect(),
             severity: Severity::Error,
         }
+        .with_schema_coordinate_from_sdl(subgraphs)
+    }
+
+    /// Like [`BuildMessage::from`], but locations in `subgraphs` also get byte offsets into the
+    /// subgraph's SDL, and the schema coordinate is found from them if there isn't one.
+    pub fn into_build_message_with_sdl(self, subgraphs: &[SubgraphDefinition]) -> BuildMessage {
+        let issue = self.with_schema_coordinate_from_sdl(subgraphs);
+        let mut other = crate::UncaughtJson::default();
+        if let Some(counterexample) = &issue.satisfiability_counterexample {
+            if let Ok(value) = serde_json::to_value(counterexample) {
+                other.insert(SATISFIABILITY_COUNTEREXAMPLE_KEY.to_string(), value);
+            }
+        }
+        BuildMessage {
+            level: issue.severity.into(),
+            message: maybe_prepend_subgraph(&issue.message, &issue.locations),
+            code: Some(issue.code.to_string()),
+            locations: issue
+                .locations
+                .iter()
+                .map(|location| {
+                    let source = subgraph_sdl(subgraphs, location.subgraph.as_deref());
+                    location.to_build_message_location(source)
+                })
+                .collect(),
+            schema_coordinate: issue.schema_coordinate,
+            step: None,
+            other,
+        }
     }
 }
 
+fn subgraph_sdl<'a>(subgraphs: &'a [SubgraphDefinition], name: Option<&str>) -> Option<&'a str> {
+    let name = name?;
+    subgraphs
+        .iter()
+        .find(|subgraph| subgraph.name == name)
+        .map(|subgraph| subgraph.sdl.as_str())
+}
+
 impl From<BuildHint> for Issue {
     fn from(hint: BuildHint) -> Issue {
         Issue {
-            code: hint.code.unwrap_or_else(|| "UNKNOWN_HINT_CODE".to_string()),
+            code: hint.code.unwrap_or_else(|| UNKNOWN_HINT_CODE.to_string()),
+            schema_coordinate: None,
             message: hint.message,
             locations: hint
                 .nodes
@@ -98,6 +242,7 @@ impl From<BuildHint> for Issue {
                 .map(Into::into)
                 .collect(),
             severity: Severity::Warning,
+            satisfiability_counterexample: None,
         }
     }
 }
@@ -109,12 +254,15 @@ impl From<FederationError> for Issue {
             FederationError::SingleFederationError(err) => {
                 err.code().definition().code().to_string()
             }
-            _ => "UNKNOWN_ERROR_CODE".to_string(),
+            _ => UNKNOWN_ERROR_CODE.to_string(),
         };
+        let message = error.to_string();
         Issue {
+            satisfiability_counterexample: satisfiability_counterexample(&code, &message),
+            schema_coordinate: None,
             code,
             // Composition failed due to an internal error, please report this: {}
-            message: error.to_string(),
+            message,
             locations: vec![],
             severity: Severity::Error,
         }
@@ -123,9 +271,13 @@ impl From<FederationError> for Issue {
 
 impl From<CompositionError> for Issue {
     fn from(error: CompositionError) -> Self {
+        let code = error.code().definition().code().to_string();
+        let message = error.to_string();
         Issue {
-            code: error.code().definition().code().to_string(),
-            message: error.to_string(),
+            satisfiability_counterexample: satisfiability_counterexample(&code, &message),
+            schema_coordinate: None,
+            code,
+            message,
             locations: convert_subgraph_locations(error.locations().to_vec()),
             severity: Severity::Error,
         }
@@ -136,10 +288,12 @@ impl From<native::CompositionHint> for Issue {
     fn from(hint: native::CompositionHint) -> Self {
         Issue {
             code: hint.code().to_string(),
+            schema_coordinate: None,
             message: hint.message,
             locations: convert_subgraph_locations(hint.locations),
             // TODO fix severity
             severity: Severity::Warning,
+            satisfiability_counterexample: None,
         }
     }
 }
@@ -153,6 +307,17 @@ impl From<native::SubgraphLocation> for SubgraphLocation {
     }
 }
 
+fn satisfiability_counterexample(
+    code: &str,
+    message: &str,
+) -> Option<Box<SatisfiabilityCounterexample>> {
+    if code == "SATISFIABILITY_ERROR" {
+        SatisfiabilityCounterexample::from_message(message).map(Box::new)
+    } else {
+        None
+    }
+}
+
 fn convert_subgraph_locations(
     locations: impl IntoIterator<Item = native::SubgraphLocation>,
 ) -> Vec<SubgraphLocation> {
@@ -185,19 +350,7 @@ fn maybe_prepend_subgraph(message: &str, locations: &[SubgraphLocation]) -> Stri
 
 impl From<Issue> for BuildMessage {
     fn from(issue: Issue) -> Self {
-        BuildMessage {
-            level: issue.severity.into(),
-            message: maybe_prepend_subgraph(&issue.message, &issue.locations),
-            code: Some(issue.code.to_string()),
-            locations: issue
-                .locations
-                .into_iter()
-                .map(|location| location.into())
-                .collect(),
-            schema_coordinate: None,
-            step: None,
-            other: Default::default(),
-        }
+        issue.into_build_message_with_sdl(&[])
     }
 }
 
@@ -246,49 +399,141 @@ impl SubgraphLocation {
     }
 }
 
-impl From<SubgraphLocation> for BuildMessageLocation {
-    fn from(location: SubgraphLocation) -> Self {
+/// Formats as `subgraph:line:column`, leaving out whichever parts are unknown.
+impl Display for SubgraphLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let subgraph = self.subgraph.as_deref().unwrap_or("<unknown subgraph>");
+        match &self.range {
+            Some(range) => write!(f, "{subgraph}:{}:{}", range.start.line, range.start.column),
+            None => write!(f, "{subgraph}"),
+        }
+    }
+}
+
+impl SubgraphLocation {
+    /// Converts to a [`BuildMessageLocation`]. If the SDL of the subgraph is given, each point
+    /// also has the byte offset of its position as both its `start` and `end`, so the range can
+    /// be sliced out of the SDL with `start.start..end.end`.
+    pub fn to_build_message_location(&self, source: Option<&str>) -> BuildMessageLocation {
+        let point = |line_column: LineColumn| {
+            let offset = source.and_then(|source| offsets::byte_offset(source, line_column));
+            BuildMessagePoint {
+                line: Some(line_column.line),
+                column: Some(line_column.column),
+                start: offset,
+                end: offset,
+            }
+        };
         BuildMessageLocation {
-            subgraph: location.subgraph,
-            start: location.range.as_ref().map(|range| BuildMessagePoint {
-                line: Some(range.start.line),
-                column: Some(range.start.column),
-                start: None,
-                end: None,
-            }),
-            end: location.range.as_ref().map(|range| BuildMessagePoint {
-                line: Some(range.end.line),
-                column: Some(range.end.column),
-                start: None,
-                end: None,
-            }),
+            subgraph: self.subgraph.clone(),
+            start: self.range.as_ref().map(|range| point(range.start)),
+            end: self.range.as_ref().map(|range| point(range.end)),
             source: None,
             other: Default::default(),
         }
     }
-}
 
-impl From<BuildMessageLocation> for SubgraphLocation {
-    fn from(location: BuildMessageLocation) -> Self {
+    /// Converts from a [`BuildMessageLocation`]. If the SDL of the subgraph is given, points
+    /// without a line and column use their byte offset instead.
+    pub fn from_build_message_location(
+        location: BuildMessageLocation,
+        source: Option<&str>,
+    ) -> Self {
+        let line_column = |point: BuildMessagePoint, offset: Option<usize>| match point {
+            BuildMessagePoint {
+                line: Some(line),
+                column: Some(column),
+                ..
+            } => Some(LineColumn { line, column }),
+            _ => offsets::line_column(source?, offset?),
+        };
         Self {
             subgraph: location.subgraph,
             range: location.start.and_then(|start| {
                 let end = location.end?;
+                let start_offset = start.start;
+                let end_offset = end.end;
                 Some(Range {
-                    start: LineColumn {
-                        line: start.line?,
-                        column: start.column?,
-                    },
-                    end: LineColumn {
-                        line: end.line?,
-                        column: end.column?,
-                    },
+                    start: line_column(start, start_offset)?,
+                    end: line_column(end, end_offset)?,
                 })
             }),
         }
     }
 }
 
+#[cfg(feature = "config")]
+impl Issue {
+    /// Like [`Issue::into_build_message_with_sdl`], but locations in subgraphs whose SDL was
+    /// concatenated from several files get the file they're in as their `source`, and their
+    /// range in that file as `fileRange`.
+    pub fn into_build_message_with_source_maps(
+        self,
+        subgraphs: &[SubgraphDefinition],
+        source_maps: &BTreeMap<String, SourceMap>,
+    ) -> BuildMessage {
+        let locations = self.locations.clone();
+        let mut message = self.into_build_message_with_sdl(subgraphs);
+        for (build_location, location) in message.locations.iter_mut().zip(&locations) {
+            let Some((path, range)) = location.file_location(source_maps) else {
+                continue;
+            };
+            build_location.source = Some(path.display().to_string());
+            build_location.other.insert(
+                FILE_RANGE_KEY.to_string(),
+                serde_json::json!({
+                    "start": { "line": range.start.line, "column": range.start.column },
+                    "end": { "line": range.end.line, "column": range.end.column },
+                }),
+            );
+        }
+        message
+    }
+}
+
+#[cfg(feature = "config")]
+impl SubgraphLocation {
+    /// The file this location is in and its range in that file, if its subgraph's SDL was
+    /// concatenated from [`SchemaSource::Files`](crate::config::SchemaSource::Files). A range
+    /// which continues into the next file is cut at the end of the first.
+    pub fn file_location<'a>(
+        &self,
+        source_maps: &'a BTreeMap<String, SourceMap>,
+    ) -> Option<(&'a Path, Range<LineColumn>)> {
+        let source_map = source_maps.get(self.subgraph.as_deref()?)?;
+        let range = self.range.as_ref()?;
+        let file = source_map.file_at(range.start.line)?;
+        let end = if file.contains(range.end.line) {
+            LineColumn {
+                line: range.end.line - file.start_line + 1,
+                column: range.end.column,
+            }
+        } else {
+            LineColumn {
+                line: file.line_count + 1,
+                column: 1,
+            }
+        };
+        let start = LineColumn {
+            line: range.start.line - file.start_line + 1,
+            column: range.start.column,
+        };
+        Some((&file.path, start..end))
+    }
+}
+
+impl From<SubgraphLocation> for BuildMessageLocation {
+    fn from(location: SubgraphLocation) -> Self {
+        location.to_build_message_location(None)
+    }
+}
+
+impl From<BuildMessageLocation> for SubgraphLocation {
+    fn from(location: BuildMessageLocation) -> Self {
+        SubgraphLocation::from_build_message_location(location, None)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct MergeResult {
     pub supergraph: String,
@@ -311,4 +556,203 @@ mod tests {
     ) {
         assert_eq!(maybe_prepend_subgraph(message, locations), expected);
     }
+
+    #[test]
+    fn test_satisfiability_counterexample_round_trip() {
+        let issue = Issue::from(GraphQLError {
+            message: "The following supergraph API query:\n{\n  a\n}\ncannot be satisfied by the subgraphs because:\n- from subgraph \"s\": cannot find field \"Query.a\".".to_string(),
+            nodes: None,
+            extensions: Some(crate::javascript::GraphQLErrorExtensions {
+                code: "SATISFIABILITY_ERROR".to_string(),
+            }),
+        });
+        let counterexample = SatisfiabilityCounterexample {
+            operation: "{\n  a\n}".to_string(),
+            path: vec!["a".to_string()],
+            subgraphs: vec!["s".to_string()],
+        };
+        assert_eq!(
+            issue.satisfiability_counterexample.as_deref(),
+            Some(&counterexample)
+        );
+
+        let message = BuildMessage::from(issue.clone());
+        assert_eq!(
+            message.other.get(SATISFIABILITY_COUNTEREXAMPLE_KEY),
+            Some(&serde_json::to_value(&counterexample).unwrap())
+        );
+        assert_eq!(Issue::from(BuildError::from(message)), issue);
+    }
+
+    #[test]
+    fn test_display() {
+        let issue =
+            Issue::new("INVALID_GRAPHQL", "Syntax error", Severity::Error).with_locations(vec![
+                SubgraphLocation {
+                    subgraph: Some("products".to_string()),
+                    range: Some(
+                        LineColumn { line: 2, column: 3 }..LineColumn { line: 2, column: 7 },
+                    ),
+                },
+                SubgraphLocation {
+                    subgraph: None,
+                    range: None,
+                },
+            ]);
+        assert_eq!(issue.to_string(), "INVALID_GRAPHQL: Syntax error");
+        assert_eq!(
+            issue.display_with_locations(),
+            "INVALID_GRAPHQL: Syntax error\n  --> products:2:3\n  --> <unknown subgraph>"
+        );
+    }
+
+    #[test]
+    fn test_schema_coordinate_is_propagated() {
+        let subgraphs = vec![SubgraphDefinition {
+            name: "products".to_string(),
+            url: "http://products".to_string(),
+            sdl: "type Product {\n  name: String\n}\n".to_string(),
+        }];
+        let location = |line: usize, column: usize| SubgraphLocation {
+            subgraph: Some("products".to_string()),
+            range: Some(LineColumn { line, column }..LineColumn { line, column }),
+        };
+        let issue = Issue::new(
+            "INVALID_FIELD_SHARING",
+            r#"Non-shareable field "Product.name" is resolved from multiple subgraphs"#,
+            Severity::Error,
+        );
+        assert_eq!(issue.schema_coordinate, None);
+
+        let message = issue
+            .clone()
+            .with_locations(vec![location(2, 3)])
+            .into_build_message_with_sdl(&subgraphs);
+        assert_eq!(message.schema_coordinate.as_deref(), Some("Product.name"));
+        assert_eq!(
+            Issue::from_build_error_with_sdl(BuildError::from(message), &subgraphs)
+                .schema_coordinate
+                .as_deref(),
+            Some("Product.name")
+        );
+
+        let message = issue
+            .with_locations(vec![location(4, 1)])
+            .into_build_message_with_sdl(&subgraphs);
+        assert_eq!(message.schema_coordinate, None);
+    }
+
+    #[test]
+    fn test_file_location() {
+        let (_, source_map) = SourceMap::concatenate(vec![
+            ("a.graphql".into(), "type Query {\n  a: A\n}\n".to_string()),
+            ("b.graphql".into(), "type A {\n  b: Int\n}\n".to_string()),
+        ]);
+        let source_maps = BTreeMap::from([("products".to_string(), source_map)]);
+        let location = |start: usize, end: usize| SubgraphLocation {
+            subgraph: Some("products".to_string()),
+            range: Some(
+                LineColumn {
+                    line: start,
+                    column: 3,
+                }..LineColumn {
+                    line: end,
+                    column: 9,
+                },
+            ),
+        };
+
+        assert_eq!(
+            location(5, 5).file_location(&source_maps),
+            Some((
+                Path::new("b.graphql"),
+                LineColumn { line: 2, column: 3 }..LineColumn { line: 2, column: 9 }
+            ))
+        );
+        assert_eq!(
+            location(2, 5).file_location(&source_maps),
+            Some((
+                Path::new("a.graphql"),
+                LineColumn { line: 2, column: 3 }..LineColumn { line: 4, column: 1 }
+            ))
+        );
+        assert_eq!(location(7, 7).file_location(&source_maps), None);
+    }
+
+    #[test]
+    fn test_build_message_with_source_maps() {
+        let (sdl, source_map) = SourceMap::concatenate(vec![
+            ("a.graphql".into(), "type Query {\n  a: A\n}\n".to_string()),
+            ("b.graphql".into(), "type A {\n  b: Int\n}\n".to_string()),
+        ]);
+        let subgraphs = vec![SubgraphDefinition {
+            name: "products".to_string(),
+            url: "http://products".to_string(),
+            sdl,
+        }];
+        let source_maps = BTreeMap::from([("products".to_string(), source_map)]);
+        let issue = Issue::new("CODE", "message", Severity::Error).with_locations(vec![
+            SubgraphLocation {
+                subgraph: Some("products".to_string()),
+                range: Some(LineColumn { line: 5, column: 3 }..LineColumn { line: 5, column: 9 }),
+            },
+            SubgraphLocation {
+                subgraph: Some("reviews".to_string()),
+                range: None,
+            },
+        ]);
+
+        let message = issue.into_build_message_with_source_maps(&subgraphs, &source_maps);
+        let location = &message.locations[0];
+        assert_eq!(location.source.as_deref(), Some("b.graphql"));
+        assert_eq!(location.start.as_ref().unwrap().line, Some(5));
+        assert_eq!(
+            location.other.get(FILE_RANGE_KEY),
+            Some(&serde_json::json!({
+                "start": { "line": 2, "column": 3 },
+                "end": { "line": 2, "column": 9 },
+            }))
+        );
+        assert_eq!(message.locations[1].source, None);
+        assert_eq!(message.schema_coordinate.as_deref(), Some("A.b"));
+    }
+
+    #[test]
+    fn test_build_message_offsets() {
+        let subgraphs = vec![SubgraphDefinition {
+            name: "products".to_string(),
+            url: "http://products".to_string(),
+            sdl: "type Query {\n  \"é😀\" a: Int b: Int\n}\n".to_string(),
+        }];
+        let issue = Issue {
+            code: "CODE".to_string(),
+            message: "message".to_string(),
+            locations: vec![SubgraphLocation {
+                subgraph: Some("products".to_string()),
+                range: Some(offsets::parsed_field_range(&subgraphs[0].sdl, "b")),
+            }],
+            severity: Severity::Error,
+            satisfiability_counterexample: None,
+            schema_coordinate: None,
+        };
+
+        let message = issue.clone().into_build_message_with_sdl(&subgraphs);
+        let location = &message.locations[0];
+        let start = location.start.as_ref().unwrap().start.unwrap();
+        let end = location.end.as_ref().unwrap().end.unwrap();
+        assert_eq!(&subgraphs[0].sdl[start..end], "b: Int");
+
+        let mut error = BuildError::from(message);
+        for node in error.nodes.iter_mut().flatten() {
+            for point in [&mut node.start, &mut node.end].into_iter().flatten() {
+                point.line = None;
+                point.column = None;
+            }
+        }
+        assert_eq!(
+            Issue::from_build_error_with_sdl(error.clone(), &subgraphs).locations,
+            issue.locations
+        );
+        assert_eq!(Issue::from(error).locations[0].range, None);
+    }
 }
@@ -0,0 +1,103 @@
+use apollo_compiler::parser::LineColumn;
+
+/// The byte offset of a one-based line and column (counting bytes, like apollo-compiler does) in
+/// `source`. Columns past the end of a line are clamped to the line break, and columns inside a
+/// character have no offset.
+pub(super) fn byte_offset(source: &str, line_column: LineColumn) -> Option<usize> {
+    let mut line_start = 0;
+    for (index, line) in source.split_inclusive('\n').enumerate() {
+        if index + 1 == line_column.line {
+            let text = line.trim_end_matches('\n').trim_end_matches('\r');
+            let offset = line_column.column.saturating_sub(1).min(text.len());
+            return text.is_char_boundary(offset).then_some(line_start + offset);
+        }
+        line_start += line.len();
+    }
+    // The start of the empty line after a trailing line break, like the end of a range which
+    // includes it
+    let after_last_line = line_column.line == source.split_inclusive('\n').count() + 1
+        && line_column.column == 1
+        && (source.is_empty() || source.ends_with('\n'));
+    after_last_line.then_some(source.len())
+}
+
+/// The one-based line and column (counting bytes) of a byte offset in `source`.
+pub(super) fn line_column(source: &str, offset: usize) -> Option<LineColumn> {
+    let before = source.get(..offset)?;
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    Some(LineColumn {
+        line: before.matches('\n').count() + 1,
+        column: before.len() - line_start + 1,
+    })
+}
+
+/// The range apollo-compiler reports for the first field definition named `field` in `sdl`.
+#[cfg(test)]
+pub(super) fn parsed_field_range(sdl: &str, field: &str) -> std::ops::Range<LineColumn> {
+    use apollo_compiler::ast::{Definition, Document};
+
+    let document = Document::parse(sdl, "subgraph.graphql").unwrap();
+    let definition = document
+        .definitions
+        .iter()
+        .find_map(|definition| match definition {
+            Definition::ObjectTypeDefinition(ty) => {
+                ty.fields.iter().find(|definition| definition.name == field)
+            }
+            _ => None,
+        })
+        .unwrap();
+    definition
+        .location()
+        .unwrap()
+        .line_column_range(&document.sources)
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_counts_the_same_columns_as_apollo_compiler() {
+        let sdl = "type Query { \"é😀\" a: Int b: Int }";
+        let range = parsed_field_range(sdl, "b");
+        let start = byte_offset(sdl, range.start).unwrap();
+        let end = byte_offset(sdl, range.end).unwrap();
+        assert_eq!(&sdl[start..end], "b: Int");
+        assert_eq!(line_column(sdl, start), Some(range.start));
+    }
+
+    const SOURCE: &str = "type Query {\r\n  \"é\" a: Int\n}";
+
+    #[rstest::rstest]
+    #[case(1, 1, Some(0))]
+    #[case(1, 12, Some(11))]
+    #[case(2, 3, Some(16))]
+    #[case(2, 5, None)]
+    #[case(2, 6, Some(19))]
+    #[case(2, 8, Some(21))]
+    #[case(2, 100, Some(27))]
+    #[case(3, 2, Some(29))]
+    #[case(4, 1, None)]
+    fn test_byte_offset(
+        #[case] line: usize,
+        #[case] column: usize,
+        #[case] expected: Option<usize>,
+    ) {
+        assert_eq!(byte_offset(SOURCE, LineColumn { line, column }), expected);
+        if let Some(offset) = expected.filter(|_| column < 100) {
+            assert_eq!(
+                line_column(SOURCE, offset),
+                Some(LineColumn { line, column })
+            );
+        }
+    }
+
+    #[test]
+    fn test_line_column_out_of_bounds() {
+        assert_eq!(line_column(SOURCE, 100), None);
+        // In the middle of "é"
+        assert_eq!(line_column(SOURCE, 18), None);
+    }
+}
@@ -0,0 +1,335 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use super::{Issue, Severity, SubgraphLocation};
+use crate::javascript::SubgraphDefinition;
+
+/// Ranges spanning more lines than this have the lines in the middle elided.
+const MAX_FRAME_LINES: usize = 6;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const BOLD_RED: &str = "\x1b[1;31m";
+const BOLD_YELLOW: &str = "\x1b[1;33m";
+const BOLD_BLUE: &str = "\x1b[1;34m";
+
+/// Renders [`Issue`]s like `rustc` diagnostics, with a code frame of the subgraph SDL for each
+/// location:
+///
+/// ```text
+/// error[INVALID_FIELD_SHARING]: Non-shareable field "Product.name" is resolved from multiple subgraphs
+///  --> products:3:5
+///   |
+/// 3 |     name: String
+///   |     ^^^^^^^^^^^^
+/// ```
+///
+/// Colour is off by default, so the output can be written to files and CI logs as-is.
+#[derive(Clone, Debug)]
+pub struct IssueRenderer<'a> {
+    sources: HashMap<&'a str, &'a str>,
+    color: bool,
+}
+
+impl<'a> IssueRenderer<'a> {
+    /// Creates a renderer for issues in the given subgraphs. Locations in any other subgraph are
+    /// rendered without a code frame.
+    pub fn new(subgraphs: &'a [SubgraphDefinition]) -> Self {
+        Self {
+            sources: subgraphs
+                .iter()
+                .map(|subgraph| (subgraph.name.as_str(), subgraph.sdl.as_str()))
+                .collect(),
+            color: false,
+        }
+    }
+
+    /// Whether to highlight the output with ANSI escape codes.
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn render(&self, issue: &Issue) -> String {
+        let mut output = String::new();
+        let (label, label_style) = match issue.severity {
+            Severity::Error => ("error", BOLD_RED),
+            Severity::Warning => ("warning", BOLD_YELLOW),
+        };
+        let _ = write!(
+            output,
+            "{}{label}[{}]{}{}: {}{}",
+            self.style(label_style),
+            issue.code,
+            self.style(RESET),
+            self.style(BOLD),
+            issue.message,
+            self.style(RESET),
+        );
+        for location in &issue.locations {
+            self.render_location(&mut output, location, label_style);
+        }
+        output
+    }
+
+    fn render_location(&self, output: &mut String, location: &SubgraphLocation, caret_style: &str) {
+        let source = location
+            .subgraph
+            .as_deref()
+            .and_then(|subgraph| self.sources.get(subgraph));
+        let (Some(source), Some(range)) = (source, &location.range) else {
+            let _ = write!(
+                output,
+                "\n  {}-->{} {location}",
+                self.style(BOLD_BLUE),
+                self.style(RESET)
+            );
+            return;
+        };
+
+        let lines: Vec<&str> = source.lines().collect();
+        let first = range.start.line.max(1);
+        let last = range.end.line.clamp(first, lines.len().max(first));
+        let gutter = last.to_string().len();
+        let _ = write!(
+            output,
+            "\n{:gutter$}{}-->{} {location}",
+            "",
+            self.style(BOLD_BLUE),
+            self.style(RESET)
+        );
+        let _ = write!(
+            output,
+            "\n{:gutter$} {}|{}",
+            "",
+            self.style(BOLD_BLUE),
+            self.style(RESET)
+        );
+
+        let mut line_number = first;
+        while line_number <= last {
+            if last - first >= MAX_FRAME_LINES && line_number == first + MAX_FRAME_LINES / 2 {
+                let _ = write!(
+                    output,
+                    "\n{}...{}",
+                    self.style(BOLD_BLUE),
+                    self.style(RESET)
+                );
+                line_number = last + 1 - MAX_FRAME_LINES / 2;
+                continue;
+            }
+            let text = lines
+                .get(line_number - 1)
+                .map(|line| line.trim_end_matches('\r'))
+                .unwrap_or_default();
+            // Columns count bytes, so they're converted to characters to line the carets up.
+            let start = if line_number == range.start.line {
+                range.start.column.saturating_sub(1)
+            } else {
+                text.len() - text.trim_start().len()
+            };
+            let end = if line_number == range.end.line {
+                range.end.column.saturating_sub(1)
+            } else {
+                text.len()
+            };
+            let start_byte = floor_char_boundary(text, start);
+            let end_byte = floor_char_boundary(text, end).max(start_byte);
+            // Keep tabs in the padding so the carets line up with the text above them.
+            let padding: String = text[..start_byte]
+                .chars()
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            // Columns past the end of the line (like a range including the line break) count as
+            // one character each.
+            let width = text[start_byte..end_byte].chars().count()
+                + end.saturating_sub(text.len().max(start));
+            let carets = "^".repeat(width.max(1));
+            let _ = write!(
+                output,
+                "\n{}{line_number:>gutter$} |{} {text}",
+                self.style(BOLD_BLUE),
+                self.style(RESET)
+            );
+            let _ = write!(
+                output,
+                "\n{:gutter$} {}|{} {padding}{}{carets}{}",
+                "",
+                self.style(BOLD_BLUE),
+                self.style(RESET),
+                self.style(caret_style),
+                self.style(RESET)
+            );
+            line_number += 1;
+        }
+    }
+
+    fn style<'s>(&self, style: &'s str) -> &'s str {
+        if self.color {
+            style
+        } else {
+            ""
+        }
+    }
+}
+
+/// The largest character boundary in `text` at or before `index`.
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use apollo_compiler::parser::LineColumn;
+
+    use super::super::offsets::parsed_field_range;
+    use super::*;
+
+    fn subgraphs() -> Vec<SubgraphDefinition> {
+        vec![SubgraphDefinition {
+            name: "products".to_string(),
+            url: "http://products".to_string(),
+            sdl: "type Query {\n  products: [Product]\n}\n\ntype Product {\n  name: String\n}\n"
+                .to_string(),
+        }]
+    }
+
+    fn issue(locations: Vec<SubgraphLocation>) -> Issue {
+        Issue {
+            code: "INVALID_FIELD_SHARING".to_string(),
+            message: "Non-shareable field \"Product.name\"".to_string(),
+            locations,
+            severity: Severity::Error,
+            satisfiability_counterexample: None,
+            schema_coordinate: Some("Product.name".to_string()),
+        }
+    }
+
+    fn location(start: (usize, usize), end: (usize, usize)) -> SubgraphLocation {
+        SubgraphLocation {
+            subgraph: Some("products".to_string()),
+            range: Some(
+                LineColumn {
+                    line: start.0,
+                    column: start.1,
+                }..LineColumn {
+                    line: end.0,
+                    column: end.1,
+                },
+            ),
+        }
+    }
+
+    #[test]
+    fn it_renders_a_code_frame() {
+        let subgraphs = subgraphs();
+        let rendered =
+            IssueRenderer::new(&subgraphs).render(&issue(vec![location((6, 3), (6, 15))]));
+        assert_eq!(
+            rendered,
+            r#"error[INVALID_FIELD_SHARING]: Non-shareable field "Product.name"
+ --> products:6:3
+  |
+6 |   name: String
+  |   ^^^^^^^^^^^^"#
+        );
+    }
+
+    #[test]
+    fn it_renders_multiple_lines_and_unknown_subgraphs() {
+        let subgraphs = subgraphs();
+        let mut issue = issue(vec![
+            location((5, 1), (7, 2)),
+            SubgraphLocation {
+                subgraph: Some("reviews".to_string()),
+                range: None,
+            },
+        ]);
+        issue.severity = Severity::Warning;
+        let rendered = IssueRenderer::new(&subgraphs).render(&issue);
+        assert_eq!(
+            rendered,
+            r#"warning[INVALID_FIELD_SHARING]: Non-shareable field "Product.name"
+ --> products:5:1
+  |
+5 | type Product {
+  | ^^^^^^^^^^^^^^
+6 |   name: String
+  |   ^^^^^^^^^^^^
+7 | }
+  | ^
+  --> reviews"#
+        );
+    }
+
+    #[test]
+    fn it_highlights_with_color() {
+        let subgraphs = subgraphs();
+        let rendered = IssueRenderer::new(&subgraphs)
+            .with_color(true)
+            .render(&issue(vec![location((6, 3), (6, 15))]));
+        assert!(rendered.starts_with("\x1b[1;31merror[INVALID_FIELD_SHARING]\x1b[0m"));
+        assert!(rendered.ends_with("\x1b[1;31m^^^^^^^^^^^^\x1b[0m"));
+    }
+
+    #[test]
+    fn it_lines_carets_up_with_non_ascii_text() {
+        let subgraphs = vec![SubgraphDefinition {
+            name: "products".to_string(),
+            url: "http://products".to_string(),
+            sdl: "type Product {\n  \"é😀\" id: ID name: String\n}\n".to_string(),
+        }];
+        let range = parsed_field_range(&subgraphs[0].sdl, "name");
+        let rendered = IssueRenderer::new(&subgraphs).render(&issue(vec![SubgraphLocation {
+            subgraph: Some("products".to_string()),
+            range: Some(range),
+        }]));
+        assert_eq!(
+            rendered,
+            r#"error[INVALID_FIELD_SHARING]: Non-shareable field "Product.name"
+ --> products:2:19
+  |
+2 |   "é😀" id: ID name: String
+  |               ^^^^^^^^^^^^"#
+        );
+    }
+
+    #[test]
+    fn it_elides_the_middle_of_long_ranges() {
+        let subgraphs = vec![SubgraphDefinition {
+            name: "products".to_string(),
+            url: "http://products".to_string(),
+            sdl: format!(
+                "type Product {{\n{}}}\n",
+                (1..=8)
+                    .map(|i| format!("  f{i}: Int\n"))
+                    .collect::<String>()
+            ),
+        }];
+        let rendered =
+            IssueRenderer::new(&subgraphs).render(&issue(vec![location((1, 1), (10, 2))]));
+        assert_eq!(
+            rendered,
+            r#"error[INVALID_FIELD_SHARING]: Non-shareable field "Product.name"
+  --> products:1:1
+   |
+ 1 | type Product {
+   | ^^^^^^^^^^^^^^
+ 2 |   f1: Int
+   |   ^^^^^^^
+ 3 |   f2: Int
+   |   ^^^^^^^
+...
+ 8 |   f7: Int
+   |   ^^^^^^^
+ 9 |   f8: Int
+   |   ^^^^^^^
+10 | }
+   | ^"#
+        );
+    }
+}
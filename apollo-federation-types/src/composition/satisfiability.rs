@@ -0,0 +1,181 @@
+use apollo_compiler::ast::{Definition, Document, Selection};
+use serde::{Deserialize, Serialize};
+
+const OPERATION_PREFIX: &str = "The following supergraph API query:\n";
+const OPERATION_SUFFIX: &str = "\ncannot be satisfied by the subgraphs because:\n";
+const SUBGRAPH_PREFIX: &str = "- from subgraph \"";
+
+/// The structured form of a `SATISFIABILITY_ERROR`, which composition otherwise only reports as
+/// prose.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SatisfiabilityCounterexample {
+    /// An example supergraph API operation which can't be satisfied by the subgraphs.
+    pub operation: String,
+
+    /// The path through `operation` to the selection which can't be satisfied. Fields are
+    /// listed by name and inline fragments as `... on Type`.
+    pub path: Vec<String>,
+
+    /// The subgraphs the operation could not be satisfied from, in the order they're reported.
+    pub subgraphs: Vec<String>,
+}
+
+impl SatisfiabilityCounterexample {
+    /// Extracts the counterexample from the message of a satisfiability error, from either the
+    /// JavaScript or the Rust implementation of composition.
+    pub fn from_message(message: &str) -> Option<Self> {
+        let (_, rest) = message.split_once(OPERATION_PREFIX)?;
+        let (operation, reasons) = rest.split_once(OPERATION_SUFFIX)?;
+        let operation = operation
+            .trim()
+            .trim_start_matches("```graphql")
+            .trim_end_matches("```")
+            .trim()
+            .to_string();
+
+        let mut subgraphs: Vec<String> = Vec::new();
+        for line in reasons.lines() {
+            let Some((subgraph, _)) = line
+                .trim_start()
+                .strip_prefix(SUBGRAPH_PREFIX)
+                .and_then(|rest| rest.split_once('"'))
+            else {
+                continue;
+            };
+            if !subgraphs.iter().any(|existing| existing == subgraph) {
+                subgraphs.push(subgraph.to_string());
+            }
+        }
+
+        Some(Self {
+            path: operation_path(&operation),
+            operation,
+            subgraphs,
+        })
+    }
+}
+
+/// Follows the first selection at each level of the operation, since example operations only ever
+/// contain a single path.
+fn operation_path(operation: &str) -> Vec<String> {
+    let document = match Document::parse(operation, "operation.graphql") {
+        Ok(document) => document,
+        Err(with_errors) => with_errors.partial,
+    };
+    let Some(mut selections) = document.definitions.iter().find_map(|definition| {
+        if let Definition::OperationDefinition(operation) = definition {
+            Some(&operation.selection_set)
+        } else {
+            None
+        }
+    }) else {
+        return Vec::new();
+    };
+
+    let mut path = Vec::new();
+    while let Some(selection) = selections.first() {
+        match selection {
+            Selection::Field(field) => {
+                path.push(field.name.to_string());
+                selections = &field.selection_set;
+            }
+            Selection::InlineFragment(fragment) => {
+                if let Some(type_condition) = &fragment.type_condition {
+                    path.push(format!("... on {type_condition}"));
+                }
+                selections = &fragment.selection_set;
+            }
+            Selection::FragmentSpread(_) => break,
+        }
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use apollo_federation::composition::{compose, CompositionOptions};
+    use apollo_federation::subgraph::typestate::Subgraph;
+
+    use super::*;
+    use crate::composition::Issue;
+
+    #[test]
+    fn it_extracts_a_counterexample() {
+        let message = r#"The following supergraph API query:
+{
+  media {
+    ... on Book {
+      title
+    }
+  }
+}
+cannot be satisfied by the subgraphs because:
+- from subgraph "books":
+  - cannot find field "Book.title".
+  - cannot move to subgraph "library" using @key(fields: "id") of "Book", the key field(s) cannot be resolved from subgraph "books".
+- from subgraph "library": cannot find field "Book.title"."#;
+
+        assert_eq!(
+            SatisfiabilityCounterexample::from_message(message),
+            Some(SatisfiabilityCounterexample {
+                operation: "{\n  media {\n    ... on Book {\n      title\n    }\n  }\n}"
+                    .to_string(),
+                path: vec![
+                    "media".to_string(),
+                    "... on Book".to_string(),
+                    "title".to_string()
+                ],
+                subgraphs: vec!["books".to_string(), "library".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn it_ignores_other_messages() {
+        assert_eq!(
+            SatisfiabilityCounterexample::from_message("Type \"Query\" is missing"),
+            None
+        );
+    }
+
+    /// Pins the message format of `apollo-federation`, whose satisfiability errors only carry a
+    /// message.
+    #[test]
+    fn it_extracts_a_counterexample_from_composition() {
+        let link = r#"extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key"])"#;
+        let subgraphs = vec![
+            Subgraph::parse(
+                "products",
+                "http://products",
+                &format!(
+                    "{link}\ntype Query {{ product: Product }}\ntype Product @key(fields: \"id\") {{ id: ID! }}"
+                ),
+            )
+            .unwrap(),
+            Subgraph::parse(
+                "reviews",
+                "http://reviews",
+                &format!(
+                    "{link}\ntype Product @key(fields: \"upc\") {{ upc: ID! rating: Int }}"
+                ),
+            )
+            .unwrap(),
+        ];
+        let errors = compose(subgraphs, CompositionOptions::default()).unwrap_err();
+        let issue = errors
+            .into_iter()
+            .map(Issue::from)
+            .find(|issue| issue.code == "SATISFIABILITY_ERROR")
+            .unwrap();
+
+        assert_eq!(
+            issue.satisfiability_counterexample.as_deref(),
+            Some(&SatisfiabilityCounterexample {
+                operation: "{\n  product {\n    upc\n  }\n}".to_string(),
+                path: vec!["product".to_string(), "upc".to_string()],
+                subgraphs: vec!["products".to_string()],
+            })
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use apollo_compiler::ast::{
+    Definition, Document, EnumValueDefinition, FieldDefinition, InputValueDefinition,
+};
+use apollo_compiler::parser::{LineColumn, SourceSpan};
+use apollo_compiler::Node;
+
+use super::offsets;
+
+/// The schema coordinate of the innermost type, field, argument, enum value, or directive
+/// definition in `sdl` which contains `position`, like `Product.price(currency:)`.
+///
+/// Positions outside any named definition, like in a `schema` definition or between
+/// definitions, have no coordinate.
+pub(super) fn schema_coordinate_at(sdl: &str, position: LineColumn) -> Option<String> {
+    let offset = offsets::byte_offset(sdl, position)?;
+    let document = match Document::parse(sdl, "subgraph.graphql") {
+        Ok(document) => document,
+        Err(with_errors) => with_errors.partial,
+    };
+    let definition = document
+        .definitions
+        .iter()
+        .find(|definition| contains(definition.location(), offset))?;
+    let name = definition.name()?;
+
+    let member = match definition {
+        Definition::DirectiveDefinition(directive) => {
+            return Some(match argument_at(&directive.arguments, offset) {
+                Some(argument) => format!("@{name}({argument}:)"),
+                None => format!("@{name}"),
+            });
+        }
+        Definition::ObjectTypeDefinition(ty) => field_at(&ty.fields, offset),
+        Definition::ObjectTypeExtension(ty) => field_at(&ty.fields, offset),
+        Definition::InterfaceTypeDefinition(ty) => field_at(&ty.fields, offset),
+        Definition::InterfaceTypeExtension(ty) => field_at(&ty.fields, offset),
+        Definition::InputObjectTypeDefinition(ty) => argument_at(&ty.fields, offset),
+        Definition::InputObjectTypeExtension(ty) => argument_at(&ty.fields, offset),
+        Definition::EnumTypeDefinition(ty) => enum_value_at(&ty.values, offset),
+        Definition::EnumTypeExtension(ty) => enum_value_at(&ty.values, offset),
+        _ => None,
+    };
+    Some(match member {
+        Some(member) => format!("{name}.{member}"),
+        None => name.to_string(),
+    })
+}
+
+fn contains(location: Option<SourceSpan>, offset: usize) -> bool {
+    location.is_some_and(|location| (location.offset()..location.end_offset()).contains(&offset))
+}
+
+/// The field at `offset`, like `price` or `price(currency:)` if it's in an argument.
+fn field_at(fields: &[Node<FieldDefinition>], offset: usize) -> Option<String> {
+    let field = fields
+        .iter()
+        .find(|field| contains(field.location(), offset))?;
+    Some(match argument_at(&field.arguments, offset) {
+        Some(argument) => format!("{}({argument}:)", field.name),
+        None => field.name.to_string(),
+    })
+}
+
+fn argument_at(arguments: &[Node<InputValueDefinition>], offset: usize) -> Option<String> {
+    arguments
+        .iter()
+        .find(|argument| contains(argument.location(), offset))
+        .map(|argument| argument.name.to_string())
+}
+
+fn enum_value_at(values: &[Node<EnumValueDefinition>], offset: usize) -> Option<String> {
+    values
+        .iter()
+        .find(|value| contains(value.location(), offset))
+        .map(|value| value.value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SDL: &str = r#"schema { query: Query }
+directive @tag(name: String) on FIELD_DEFINITION
+type Query { product: Product }
+type Product {
+  name: String
+  price(currency: String): Int
+}
+extend enum Currency { EUR }
+input Filter { name: String }
+"#;
+
+    #[rstest::rstest]
+    #[case(1, 10, None)]
+    #[case(2, 1, Some("@tag"))]
+    #[case(2, 16, Some("@tag(name:)"))]
+    #[case(3, 6, Some("Query"))]
+    #[case(4, 14, Some("Product"))]
+    #[case(5, 3, Some("Product.name"))]
+    #[case(6, 3, Some("Product.price"))]
+    #[case(6, 10, Some("Product.price(currency:)"))]
+    #[case(8, 24, Some("Currency.EUR"))]
+    #[case(9, 16, Some("Filter.name"))]
+    #[case(10, 1, None)]
+    fn test_schema_coordinate_at(
+        #[case] line: usize,
+        #[case] column: usize,
+        #[case] expected: Option<&str>,
+    ) {
+        assert_eq!(
+            schema_coordinate_at(SDL, LineColumn { line, column }).as_deref(),
+            expected
+        );
+    }
+}
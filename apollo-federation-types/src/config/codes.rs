@@ -0,0 +1,21 @@
+//! The codes of [`ConfigError`](super::ConfigError)s, as returned by
+//! [`ConfigError::code`](super::ConfigError::code).
+
+/// The supergraph config couldn't be parsed.
+pub const CONFIG_INVALID: &str = "CONFIG_INVALID";
+/// A file the supergraph config refers to doesn't exist.
+pub const CONFIG_FILE_MISSING: &str = "CONFIG_FILE_MISSING";
+/// Supergraph configs extend each other in a cycle.
+pub const CONFIG_EXTENDS_CYCLE: &str = "CONFIG_EXTENDS_CYCLE";
+/// Merged supergraph configs set the same key differently.
+pub const CONFIG_MERGE_CONFLICT: &str = "CONFIG_MERGE_CONFLICT";
+/// An environment variable used in the supergraph config isn't set and has no default.
+pub const CONFIG_ENV_VAR_MISSING: &str = "CONFIG_ENV_VAR_MISSING";
+/// The schemas of some subgraphs couldn't be resolved.
+pub const SUBGRAPHS_NOT_RESOLVED: &str = "SUBGRAPHS_NOT_RESOLVED";
+/// The supergraph config has no subgraphs.
+pub const NO_SUBGRAPHS_FOUND: &str = "NO_SUBGRAPHS_FOUND";
+/// A subgraph's schema couldn't be resolved.
+pub const SUBGRAPH_SCHEMA_NOT_RESOLVED: &str = "SUBGRAPH_SCHEMA_NOT_RESOLVED";
+/// The command printing a subgraph's schema failed, timed out or printed too much.
+pub const SUBGRAPH_COMMAND_FAILED: &str = "SUBGRAPH_COMMAND_FAILED";
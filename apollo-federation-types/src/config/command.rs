@@ -0,0 +1,326 @@
+use std::{
+    collections::HashMap,
+    io::Read,
+    path::Path,
+    process::{Child, Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::config::{CommandFailure, ConfigError, ConfigResult};
+
+/// How long a command may run unless its `timeout_secs` says otherwise.
+pub(crate) const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The most SDL a command may print, which is far more than any real subgraph.
+const MAX_STDOUT_BYTES: usize = 32 * 1024 * 1024;
+
+/// How much of stderr is kept for errors.
+const MAX_STDERR_BYTES: usize = 64 * 1024;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Runs the command of a [`SchemaSource::Command`](crate::config::SchemaSource::Command),
+/// returning its stdout. `working_dir` is relative to `config_dir`, which is also the default.
+///
+/// The command is killed, along with any processes it started, if it runs for longer than
+/// `timeout` or prints more than [`MAX_STDOUT_BYTES`]. Processes it started in the background
+/// count towards the timeout while they keep its output open.
+pub(crate) fn run_command(
+    subgraph_name: &str,
+    command: &str,
+    args: &[String],
+    working_dir: Option<&Path>,
+    env: &HashMap<String, String>,
+    timeout: Duration,
+    config_dir: &Path,
+) -> ConfigResult<String> {
+    let command_line = std::iter::once(command)
+        .chain(args.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let not_run = |err: std::io::Error| ConfigError::SchemaNotResolved {
+        subgraph_name: subgraph_name.to_string(),
+        message: format!("could not run \"{command_line}\": {err}"),
+    };
+    let mut command_builder = Command::new(command);
+    // Put the command in its own process group, so that it can be killed along with the
+    // processes it starts
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command_builder, 0);
+    let mut child = command_builder
+        .args(args)
+        .current_dir(working_dir.map_or(config_dir.to_path_buf(), |dir| config_dir.join(dir)))
+        .envs(env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(not_run)?;
+    let stdout = PipeReader::spawn(child.stdout.take(), MAX_STDOUT_BYTES);
+    let stderr = PipeReader::spawn(child.stderr.take(), MAX_STDERR_BYTES);
+
+    let deadline = Instant::now() + timeout;
+    let failed = |failure: CommandFailure, stderr: Vec<u8>| ConfigError::CommandFailed {
+        subgraph_name: subgraph_name.to_string(),
+        command: command_line.clone(),
+        failure,
+        stderr: String::from_utf8_lossy(&stderr).trim().to_string(),
+    };
+    let status = loop {
+        let killed_for = if stdout.truncated() {
+            CommandFailure::OutputTooLarge(MAX_STDOUT_BYTES)
+        } else if Instant::now() >= deadline {
+            CommandFailure::TimedOut(timeout)
+        } else if let Some(status) = child.try_wait().map_err(not_run)? {
+            break status;
+        } else {
+            thread::sleep(POLL_INTERVAL);
+            continue;
+        };
+        kill(&mut child);
+        // Processes the command started may still hold the pipes open, so don't wait for them
+        return Err(failed(killed_for, stderr.contents()));
+    };
+
+    // Processes the command started in the background may keep the pipes open after it exits
+    let stdout = match stdout.finish(deadline) {
+        Ok(stdout) => stdout,
+        Err(_) => {
+            kill(&mut child);
+            return Err(failed(
+                CommandFailure::TimedOut(timeout),
+                stderr.finish(deadline).unwrap_or_else(|contents| contents),
+            ));
+        }
+    };
+    let mut finish_stderr = || {
+        stderr.finish(deadline).unwrap_or_else(|contents| {
+            kill(&mut child);
+            contents
+        })
+    };
+    if !status.success() {
+        let failure = status
+            .code()
+            .map_or(CommandFailure::Signaled, CommandFailure::Exited);
+        return Err(failed(failure, finish_stderr()));
+    }
+    if stdout.len() > MAX_STDOUT_BYTES {
+        return Err(failed(
+            CommandFailure::OutputTooLarge(MAX_STDOUT_BYTES),
+            finish_stderr(),
+        ));
+    }
+    String::from_utf8(stdout).map_err(|_| ConfigError::SchemaNotResolved {
+        subgraph_name: subgraph_name.to_string(),
+        message: format!("\"{command_line}\" printed SDL which isn't valid UTF-8"),
+    })
+}
+
+/// Kills the command, and on Unix every other process in its process group.
+fn kill(child: &mut Child) {
+    #[cfg(unix)]
+    if let Ok(pid) = libc::pid_t::try_from(child.id()) {
+        // SAFETY: `kill` has no memory safety requirements. The process group has the command's
+        // id, which can't have been reused while the command hasn't been waited for, or while
+        // the processes it started are still in the group.
+        unsafe {
+            libc::kill(-pid, libc::SIGKILL);
+        }
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Reads a pipe to its end on another thread, keeping at most `limit` bytes (plus one, to tell
+/// when there was more) so that the process writing to it never blocks.
+struct PipeReader {
+    buffer: Arc<Mutex<Vec<u8>>>,
+    truncated: Arc<AtomicBool>,
+    /// Disconnected when the reading thread is done.
+    done: Receiver<()>,
+}
+
+impl PipeReader {
+    fn spawn(pipe: Option<impl Read + Send + 'static>, limit: usize) -> Self {
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let truncated = Arc::new(AtomicBool::new(false));
+        let (done_sender, done) = mpsc::channel();
+        thread::spawn({
+            let buffer = buffer.clone();
+            let truncated = truncated.clone();
+            move || {
+                let _done_sender = done_sender;
+                let Some(mut pipe) = pipe else {
+                    return;
+                };
+                let mut chunk = [0; 8192];
+                while let Ok(read @ 1..) = pipe.read(&mut chunk) {
+                    let mut buffer = buffer.lock().unwrap_or_else(|err| err.into_inner());
+                    let room = (limit + 1).saturating_sub(buffer.len());
+                    buffer.extend_from_slice(&chunk[..read.min(room)]);
+                    if buffer.len() > limit {
+                        truncated.store(true, Ordering::Relaxed);
+                    }
+                }
+            }
+        });
+        Self {
+            buffer,
+            truncated,
+            done,
+        }
+    }
+
+    fn truncated(&self) -> bool {
+        self.truncated.load(Ordering::Relaxed)
+    }
+
+    /// What has been read so far.
+    fn contents(&self) -> Vec<u8> {
+        self.buffer
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    /// Waits until `deadline` for the pipe to close, returning everything that was kept, or what
+    /// had been read by the deadline as an error.
+    fn finish(&self, deadline: Instant) -> Result<Vec<u8>, Vec<u8>> {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        match self.done.recv_timeout(timeout) {
+            Err(RecvTimeoutError::Timeout) => Err(self.contents()),
+            Ok(()) | Err(RecvTimeoutError::Disconnected) => {
+                let mut buffer = self.buffer.lock().unwrap_or_else(|err| err.into_inner());
+                Ok(std::mem::take(&mut *buffer))
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use assert_fs::{prelude::*, TempDir};
+
+    use super::*;
+
+    fn sh(script: &str) -> Vec<String> {
+        vec!["-c".to_string(), script.to_string()]
+    }
+
+    #[test]
+    fn it_returns_stdout() {
+        let config_dir = TempDir::new().unwrap();
+        config_dir.child("schema").create_dir_all().unwrap();
+        let env = HashMap::from([("FIELD".to_string(), "a".to_string())]);
+
+        let sdl = run_command(
+            "products",
+            "sh",
+            &sh("echo \"type Query { $FIELD: Int } # $(basename \"$PWD\")\""),
+            Some(Path::new("schema")),
+            &env,
+            DEFAULT_COMMAND_TIMEOUT,
+            config_dir.path(),
+        )
+        .unwrap();
+        assert_eq!(sdl, "type Query { a: Int } # schema\n");
+    }
+
+    #[test]
+    fn it_reports_exit_code_and_stderr() {
+        let error = run_command(
+            "products",
+            "sh",
+            &sh("echo 'cannot build schema' >&2; exit 3"),
+            None,
+            &HashMap::new(),
+            DEFAULT_COMMAND_TIMEOUT,
+            Path::new("."),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Command \"sh -c echo 'cannot build schema' >&2; exit 3\" for subgraph \"products\" exited with code 3: cannot build schema."
+        );
+        assert_eq!(error.code().as_deref(), Some("SUBGRAPH_COMMAND_FAILED"));
+    }
+
+    #[test]
+    fn it_kills_commands_which_time_out() {
+        let started = Instant::now();
+        let error = run_command(
+            "products",
+            "sh",
+            &sh("echo 'starting' >&2; exec sleep 10"),
+            None,
+            &HashMap::new(),
+            Duration::from_millis(200),
+            Path::new("."),
+        )
+        .unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(matches!(
+            &error,
+            ConfigError::CommandFailed {
+                failure: CommandFailure::TimedOut(_),
+                stderr,
+                ..
+            } if stderr == "starting"
+        ));
+        assert_eq!(
+            error.to_string(),
+            "Command \"sh -c echo 'starting' >&2; exec sleep 10\" for subgraph \"products\" timed out after 0.2s: starting."
+        );
+    }
+
+    #[test]
+    fn it_kills_commands_which_print_too_much() {
+        let error = run_command(
+            "products",
+            "sh",
+            &sh("exec yes"),
+            None,
+            &HashMap::new(),
+            DEFAULT_COMMAND_TIMEOUT,
+            Path::new("."),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error,
+            ConfigError::CommandFailed {
+                failure: CommandFailure::OutputTooLarge(MAX_STDOUT_BYTES),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn it_times_out_while_background_processes_keep_output_open() {
+        let started = Instant::now();
+        let error = run_command(
+            "products",
+            "sh",
+            &sh("echo 'type Query { a: Int }'; sleep 8 &"),
+            None,
+            &HashMap::new(),
+            Duration::from_secs(1),
+            Path::new("."),
+        )
+        .unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(matches!(
+            error,
+            ConfigError::CommandFailed {
+                failure: CommandFailure::TimedOut(_),
+                ..
+            }
+        ));
+    }
+}
@@ -1,10 +1,31 @@
+use std::{fmt, time::Duration};
+
 use thiserror::Error;
 
+use super::codes::*;
+
 #[derive(Error, Debug)]
 pub enum ConfigError {
     #[error("Could not parse supergraph config: {message}.")]
     InvalidConfiguration { message: String },
 
+    /// The supergraph config couldn't be deserialized, at a known location.
+    #[error(
+        "Could not parse supergraph config at {}: {message}.",
+        parse_error_location(file_path.as_deref(), *line, *column, key_path.as_deref())
+    )]
+    ParseError {
+        message: String,
+        /// The path of the config file, if it was read from one.
+        file_path: Option<String>,
+        /// One-based.
+        line: usize,
+        /// One-based.
+        column: usize,
+        /// The path to the offending value, like `subgraphs.films.schema`, if known.
+        key_path: Option<String>,
+    },
+
     #[error("File \"{file_path}\" not found: {message}.")]
     MissingFile { file_path: String, message: String },
 
@@ -13,6 +34,31 @@ pub enum ConfigError {
 
     #[error("No subgraphs were found in the supergraph config.")]
     NoSubgraphsFound,
+
+    #[error("Could not resolve the schema of subgraph \"{subgraph_name}\": {message}.")]
+    SchemaNotResolved {
+        subgraph_name: String,
+        message: String,
+    },
+
+    /// The command of a [`SchemaSource::Command`](crate::config::SchemaSource::Command) failed.
+    #[error("Command \"{command}\" for subgraph \"{subgraph_name}\" {failure}: {stderr}.")]
+    CommandFailed {
+        subgraph_name: String,
+        command: String,
+        failure: CommandFailure,
+        stderr: String,
+    },
+
+    /// A supergraph config extends itself, through the listed files.
+    #[error("Supergraph config extends itself: {}.", file_paths.join(" -> "))]
+    ExtendsCycle { file_paths: Vec<String> },
+
+    #[error("Could not merge supergraph configs, which set {} differently.", key_paths.join(", "))]
+    MergeConflict { key_paths: Vec<String> },
+
+    #[error("Environment variable \"{name}\" used in {key_path} is not set.")]
+    MissingEnvironmentVariable { name: String, key_path: String },
 }
 
 impl ConfigError {
@@ -20,8 +66,92 @@ impl ConfigError {
         self.to_string()
     }
 
+    /// A stable code for this kind of error, which is used as the code of the [`BuildMessage`]
+    /// or [`BuildError`] created from it.
+    ///
+    /// [`BuildMessage`]: crate::build_plugin::BuildMessage
+    /// [`BuildError`]: crate::rover::BuildError
     pub fn code(&self) -> Option<String> {
-        // TODO: Eventually add codes to these?
-        None
+        let code = match self {
+            ConfigError::InvalidConfiguration { .. } | ConfigError::ParseError { .. } => {
+                CONFIG_INVALID
+            }
+            ConfigError::MissingFile { .. } => CONFIG_FILE_MISSING,
+            ConfigError::SubgraphsNotResolved { .. } => SUBGRAPHS_NOT_RESOLVED,
+            ConfigError::NoSubgraphsFound => NO_SUBGRAPHS_FOUND,
+            ConfigError::SchemaNotResolved { .. } => SUBGRAPH_SCHEMA_NOT_RESOLVED,
+            ConfigError::CommandFailed { .. } => SUBGRAPH_COMMAND_FAILED,
+            ConfigError::ExtendsCycle { .. } => CONFIG_EXTENDS_CYCLE,
+            ConfigError::MergeConflict { .. } => CONFIG_MERGE_CONFLICT,
+            ConfigError::MissingEnvironmentVariable { .. } => CONFIG_ENV_VAR_MISSING,
+        };
+        Some(code.to_string())
+    }
+}
+
+fn parse_error_location(
+    file_path: Option<&str>,
+    line: usize,
+    column: usize,
+    key_path: Option<&str>,
+) -> String {
+    let mut location = format!("{}:{line}:{column}", file_path.unwrap_or("<input>"));
+    if let Some(key_path) = key_path {
+        location.push_str(&format!(" ({key_path})"));
+    }
+    location
+}
+
+/// Why the command of a [`SchemaSource::Command`](crate::config::SchemaSource::Command) failed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CommandFailure {
+    /// The command exited with a non-zero code.
+    Exited(i32),
+    /// The command was terminated by a signal.
+    Signaled,
+    /// The command was killed after running for this long.
+    TimedOut(Duration),
+    /// The command was killed after printing more than this many bytes.
+    OutputTooLarge(usize),
+}
+
+/// Formats as the end of a sentence starting with the command, like `exited with code 1`.
+impl fmt::Display for CommandFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandFailure::Exited(exit_code) => write!(f, "exited with code {exit_code}"),
+            CommandFailure::Signaled => write!(f, "was terminated by a signal"),
+            CommandFailure::TimedOut(timeout) => {
+                write!(f, "timed out after {}s", timeout.as_secs_f64())
+            }
+            CommandFailure::OutputTooLarge(limit) => {
+                write!(f, "printed more than {limit} bytes of SDL")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConfigError;
+    use crate::build_plugin::PluginResult;
+    use crate::rover::BuildErrors;
+
+    #[test]
+    fn it_has_a_code_in_build_output() {
+        let plugin_result = PluginResult::from(ConfigError::NoSubgraphsFound);
+        assert_eq!(
+            plugin_result.build_messages[0].code.as_deref(),
+            Some("NO_SUBGRAPHS_FOUND")
+        );
+
+        let build_errors = BuildErrors::from(ConfigError::MissingFile {
+            file_path: "supergraph.yaml".to_string(),
+            message: "No such file or directory".to_string(),
+        });
+        assert_eq!(
+            build_errors.build_errors[0].code.as_deref(),
+            Some("CONFIG_FILE_MISSING")
+        );
     }
 }
@@ -0,0 +1,970 @@
+use std::{
+    fmt::{self, Display},
+    iter,
+    ops::Range,
+};
+
+use serde_yaml::Value;
+use yaml_rust::{
+    parser::{Event, MarkedEventReceiver, Parser},
+    scanner::{Marker, TScalarStyle},
+    Yaml, YamlLoader,
+};
+
+use crate::config::{
+    ConfigError, ConfigResult, FederationVersion, SubgraphConfig, SupergraphConfig,
+};
+
+const SUBGRAPHS_KEY: &str = "subgraphs";
+const FEDERATION_VERSION_KEY: &str = "federation_version";
+const ROUTING_URL_KEY: &str = "routing_url";
+const DEFAULT_INDENT: usize = 2;
+
+/// A supergraph config YAML document which can be edited without losing its comments, key
+/// order, or formatting.
+///
+/// The document is read with a YAML event parser to find what to edit, and edits only rewrite
+/// the lines or scalars they change, keeping the quoting of replaced scalars. `subgraphs` has to
+/// be a block mapping, as it's written unless it's empty (`subgraphs: {}`), and edits are refused
+/// where they'd have to rewrite block scalars, multi-line scalars, or flow mappings. An edit is
+/// undone if the edited document doesn't parse to the expected config. Displays as the edited
+/// YAML.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SupergraphConfigDocument {
+    /// Each line, without its line break.
+    lines: Vec<String>,
+    line_break: &'static str,
+    trailing_line_break: bool,
+}
+
+/// A node of the document, as read by the parser.
+#[derive(Clone, Debug)]
+enum Node {
+    Scalar {
+        value: String,
+        style: TScalarStyle,
+        start: Position,
+    },
+    Mapping {
+        flow: bool,
+        start: Position,
+        entries: Vec<Entry>,
+    },
+    /// A sequence or an alias, which are never edited in place.
+    Other { start: Position },
+}
+
+/// A mapping entry with a scalar key.
+#[derive(Clone, Debug)]
+struct Entry {
+    name: String,
+    key: Node,
+    value: Node,
+}
+
+/// Where a node starts, as a line index and a column in chars.
+#[derive(Clone, Copy, Debug)]
+struct Position {
+    line: usize,
+    column: usize,
+}
+
+/// The `subgraphs` entry of the document.
+struct Subgraphs {
+    key: Node,
+    value: Node,
+    entries: Vec<SubgraphEntry>,
+}
+
+/// Where a subgraph is in the document, as line indexes.
+struct SubgraphEntry {
+    name: String,
+    /// The comments directly above the subgraph, then its key and value, then any blank lines
+    /// before the next subgraph.
+    lines: Range<usize>,
+    key_line: usize,
+    /// The index after the last line of the subgraph's value.
+    value_end: usize,
+    indent: usize,
+    value: Node,
+}
+
+impl SupergraphConfigDocument {
+    /// Parses a YAML document, which must be a valid supergraph config.
+    pub fn parse(yaml: &str) -> ConfigResult<Self> {
+        SupergraphConfig::new_from_yaml(yaml)?;
+        Ok(Self {
+            lines: yaml.lines().map(str::to_string).collect(),
+            line_break: if yaml.contains("\r\n") { "\r\n" } else { "\n" },
+            trailing_line_break: yaml.ends_with('\n') || yaml.is_empty(),
+        })
+    }
+
+    /// Parses the edited document.
+    pub fn config(&self) -> ConfigResult<SupergraphConfig> {
+        SupergraphConfig::new_from_yaml(&self.to_string())
+    }
+
+    /// The names of the subgraphs, in the order they're written.
+    pub fn subgraph_names(&self) -> ConfigResult<Vec<String>> {
+        Ok(self
+            .subgraphs()?
+            .entries
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect())
+    }
+
+    /// Adds a subgraph after the others, or replaces the config of an existing one. Comments
+    /// above a replaced subgraph are kept, but comments inside its config are not.
+    pub fn set_subgraph(&mut self, name: &str, subgraph: &SubgraphConfig) -> ConfigResult<()> {
+        self.edit(
+            |config| {
+                config
+                    .subgraphs_mut()
+                    .insert(name.to_string(), subgraph.clone());
+            },
+            |document| document.write_subgraph(name, subgraph),
+        )
+    }
+
+    /// Removes a subgraph and the comments directly above it, returning whether it was there.
+    pub fn remove_subgraph(&mut self, name: &str) -> ConfigResult<bool> {
+        let subgraphs = self.subgraphs()?;
+        let Some(index) = subgraphs
+            .entries
+            .iter()
+            .position(|entry| entry.name == name)
+        else {
+            return Ok(false);
+        };
+        self.edit(
+            |config| {
+                config.subgraphs_mut().remove(name);
+            },
+            |document| document.delete_subgraph(&subgraphs, index),
+        )?;
+        Ok(true)
+    }
+
+    /// Sets or removes the routing URL of a subgraph in place, keeping the rest of its config.
+    pub fn set_routing_url(&mut self, name: &str, routing_url: Option<&str>) -> ConfigResult<()> {
+        self.edit(
+            |config| {
+                if let Some(subgraph) = config.subgraphs_mut().get_mut(name) {
+                    subgraph.routing_url = routing_url.map(str::to_string);
+                }
+            },
+            |document| document.write_routing_url(name, routing_url),
+        )
+    }
+
+    /// Sets or removes `federation_version` in place. A new one is added above `subgraphs`.
+    pub fn set_federation_version(
+        &mut self,
+        federation_version: Option<&FederationVersion>,
+    ) -> ConfigResult<()> {
+        self.edit(
+            |config| *config.federation_version_mut() = federation_version.cloned(),
+            |document| {
+                let root = document.root()?;
+                let existing = root
+                    .iter()
+                    .find(|entry| entry.name == FEDERATION_VERSION_KEY);
+                let new_line = match root.iter().find(|entry| entry.name == SUBGRAPHS_KEY) {
+                    Some(subgraphs) => {
+                        document.leading_comments_start(subgraphs.key.start().line, 0)
+                    }
+                    None => document.lines.len(),
+                };
+                let value = federation_version.map(FederationVersion::to_config_string);
+                document.write_entry(
+                    FEDERATION_VERSION_KEY,
+                    existing,
+                    value.as_deref(),
+                    version_scalar,
+                    (new_line, 0),
+                )
+            },
+        )
+    }
+
+    /// Makes an edit, then checks that the document parses to the config `expect` makes of the
+    /// one before it, undoing the edit if it doesn't.
+    fn edit(
+        &mut self,
+        expect: impl FnOnce(&mut SupergraphConfig),
+        edit: impl FnOnce(&mut Self) -> ConfigResult<()>,
+    ) -> ConfigResult<()> {
+        let mut expected = self.config()?;
+        expect(&mut expected);
+        let lines = self.lines.clone();
+        let result = edit(self).and_then(|()| match self.config() {
+            Ok(config) if config == expected => Ok(()),
+            _ => Err(ConfigError::InvalidConfiguration {
+                message: "the edit would change the document in ways it wasn't meant to"
+                    .to_string(),
+            }),
+        });
+        if result.is_err() {
+            self.lines = lines;
+        }
+        result
+    }
+
+    fn write_subgraph(&mut self, name: &str, subgraph: &SubgraphConfig) -> ConfigResult<()> {
+        let subgraphs = self.subgraphs()?;
+        let entries = &subgraphs.entries;
+        let indent = entries.first().map_or(DEFAULT_INDENT, |entry| entry.indent);
+        // Indent the config like the other subgraphs are
+        let step = entries
+            .iter()
+            .find_map(|entry| match &entry.value {
+                Node::Mapping {
+                    flow: false,
+                    entries,
+                    ..
+                } => entries
+                    .first()
+                    .map(|first| first.key.start().column.saturating_sub(entry.indent)),
+                _ => None,
+            })
+            .filter(|step| *step > 0)
+            .unwrap_or(indent.max(DEFAULT_INDENT));
+        let value =
+            serde_yaml::to_value(subgraph).map_err(|err| ConfigError::InvalidConfiguration {
+                message: format!("could not serialize subgraph \"{name}\": {err}"),
+            })?;
+        let mut lines = vec![format!(
+            "{}{}:",
+            " ".repeat(indent),
+            scalar(name, TScalarStyle::Plain)
+        )];
+        lines.extend(
+            block_lines(&value, step)
+                .into_iter()
+                .map(|line| format!("{}{line}", " ".repeat(indent + step))),
+        );
+
+        if let Some(entry) = entries.iter().find(|entry| entry.name == name) {
+            self.lines.splice(entry.key_line..entry.value_end, lines);
+            return Ok(());
+        }
+        let insert_at = match entries.last() {
+            Some(last) => {
+                let spaced =
+                    entries.len() > 1 && self.lines[entries[1].lines.start - 1].trim().is_empty();
+                if spaced {
+                    lines.insert(0, String::new());
+                }
+                last.value_end
+            }
+            None => {
+                self.remove_empty_mapping(&subgraphs.value)?;
+                subgraphs.key.start().line + 1
+            }
+        };
+        self.lines.splice(insert_at..insert_at, lines);
+        Ok(())
+    }
+
+    fn delete_subgraph(&mut self, subgraphs: &Subgraphs, index: usize) -> ConfigResult<()> {
+        let mut lines = subgraphs.entries[index].lines.clone();
+        if index > 0 && index + 1 == subgraphs.entries.len() {
+            // Take the blank lines between the last subgraph and the one before it
+            while self.lines[lines.start - 1].trim().is_empty() {
+                lines.start -= 1;
+            }
+        }
+        self.lines.drain(lines);
+        if subgraphs.entries.len() == 1 {
+            let (line, span) = self
+                .scalar_span(&subgraphs.key)
+                .ok_or_else(|| not_editable(SUBGRAPHS_KEY))?;
+            let colon = self.lines[line][span.end..]
+                .find(':')
+                .map(|offset| span.end + offset + 1)
+                .ok_or_else(|| not_editable(SUBGRAPHS_KEY))?;
+            self.lines[line].insert_str(colon, " {}");
+        }
+        Ok(())
+    }
+
+    fn write_routing_url(&mut self, name: &str, routing_url: Option<&str>) -> ConfigResult<()> {
+        let subgraphs = self.subgraphs()?;
+        let entry = subgraphs
+            .entries
+            .iter()
+            .find(|entry| entry.name == name)
+            .ok_or_else(|| ConfigError::InvalidConfiguration {
+                message: format!("there is no subgraph named \"{name}\" to update"),
+            })?;
+        let Node::Mapping {
+            flow: false,
+            entries,
+            ..
+        } = &entry.value
+        else {
+            return Err(ConfigError::InvalidConfiguration {
+                message: format!(
+                    "subgraph \"{name}\" must be written as a block mapping to be edited"
+                ),
+            });
+        };
+        let existing = entries.iter().find(|entry| entry.name == ROUTING_URL_KEY);
+        let new_entry_at = entries.first().map_or(
+            (entry.key_line + 1, entry.indent + DEFAULT_INDENT),
+            |first| (first.key.start().line, first.key.start().column),
+        );
+        self.write_entry(ROUTING_URL_KEY, existing, routing_url, scalar, new_entry_at)
+    }
+
+    /// Replaces or removes the scalar value of an existing `key: value` entry, or adds a new
+    /// entry at the given line and indent.
+    fn write_entry(
+        &mut self,
+        key: &str,
+        existing: Option<&Entry>,
+        value: Option<&str>,
+        write_scalar: fn(&str, TScalarStyle) -> String,
+        (new_line, new_indent): (usize, usize),
+    ) -> ConfigResult<()> {
+        match (existing, value) {
+            (Some(entry), Some(value)) => {
+                let (line, span) = self
+                    .scalar_span(&entry.value)
+                    .ok_or_else(|| not_editable(key))?;
+                let Node::Scalar { style, .. } = entry.value else {
+                    unreachable!("only scalars have spans");
+                };
+                self.lines[line].replace_range(span, &write_scalar(value, style));
+            }
+            (Some(entry), None) => {
+                let (line, _) = self
+                    .scalar_span(&entry.value)
+                    .filter(|(line, _)| *line == entry.key.start().line)
+                    .ok_or_else(|| not_editable(key))?;
+                self.lines.remove(line);
+            }
+            (None, Some(value)) => self.lines.insert(
+                new_line,
+                format!(
+                    "{}{key}: {}",
+                    " ".repeat(new_indent),
+                    write_scalar(value, TScalarStyle::Plain)
+                ),
+            ),
+            (None, None) => {}
+        }
+        Ok(())
+    }
+
+    /// Rewrites `subgraphs: {}` to `subgraphs:`, keeping any comment after it.
+    fn remove_empty_mapping(&mut self, value: &Node) -> ConfigResult<()> {
+        let Node::Mapping {
+            flow: true, start, ..
+        } = value
+        else {
+            return Ok(());
+        };
+        let begin = self
+            .byte_offset(*start)
+            .ok_or_else(|| not_editable(SUBGRAPHS_KEY))?;
+        let line = &mut self.lines[start.line];
+        let end = line[begin..]
+            .find('}')
+            .map(|offset| begin + offset + 1)
+            .ok_or_else(|| not_editable(SUBGRAPHS_KEY))?;
+        let begin = if line[..begin].ends_with(' ') {
+            begin - 1
+        } else {
+            begin
+        };
+        line.replace_range(begin..end, "");
+        Ok(())
+    }
+
+    /// The entries of the top-level mapping.
+    fn root(&self) -> ConfigResult<Vec<Entry>> {
+        let source = self.to_string();
+        let mut builder = TreeBuilder {
+            source: source.chars().collect(),
+            stack: Vec::new(),
+            root: None,
+        };
+        Parser::new(source.chars())
+            .load(&mut builder, false)
+            .map_err(|err| ConfigError::InvalidConfiguration {
+                message: format!("the document is not valid YAML: {err}"),
+            })?;
+        match builder.root {
+            Some(Node::Mapping {
+                flow: false,
+                entries,
+                ..
+            }) => Ok(entries),
+            _ => Err(ConfigError::InvalidConfiguration {
+                message: "the document must be written as a block mapping to be edited".to_string(),
+            }),
+        }
+    }
+
+    fn subgraphs(&self) -> ConfigResult<Subgraphs> {
+        let mut root = self.root()?;
+        let index = root
+            .iter()
+            .position(|entry| entry.name == SUBGRAPHS_KEY)
+            .ok_or_else(|| ConfigError::InvalidConfiguration {
+                message: "the document has no subgraphs".to_string(),
+            })?;
+        let Entry { key, value, .. } = root.remove(index);
+        // The subgraphs end before the next top-level key, and the comments above it
+        let end = root.get(index).map_or(self.lines.len(), |next| {
+            self.leading_comments_start(next.key.start().line, 0)
+        });
+        let entries = match &value {
+            Node::Mapping {
+                flow: false,
+                entries,
+                ..
+            } => entries.as_slice(),
+            Node::Mapping {
+                flow: true,
+                entries,
+                ..
+            } if entries.is_empty() => &[],
+            _ => {
+                return Err(ConfigError::InvalidConfiguration {
+                    message: "subgraphs must be written as a block mapping to be edited"
+                        .to_string(),
+                })
+            }
+        };
+
+        let mut subgraphs: Vec<SubgraphEntry> = Vec::new();
+        for entry in entries {
+            let Position {
+                line: key_line,
+                column: indent,
+            } = entry.key.start();
+            let start = self.leading_comments_start(key_line, indent);
+            if let Some(previous) = subgraphs.last_mut() {
+                previous.lines.end = start;
+            }
+            let value_end = self.value_end(key_line, end, indent);
+            subgraphs.push(SubgraphEntry {
+                name: entry.name.clone(),
+                lines: start..value_end,
+                key_line,
+                value_end,
+                indent,
+                value: entry.value.clone(),
+            });
+        }
+        Ok(Subgraphs {
+            key,
+            value: value.clone(),
+            entries: subgraphs,
+        })
+    }
+
+    /// The line and byte range of a scalar, or `None` for block scalars, scalars written over
+    /// more than one line, and anything else which can't be replaced in place.
+    fn scalar_span(&self, node: &Node) -> Option<(usize, Range<usize>)> {
+        let Node::Scalar {
+            value,
+            style,
+            start,
+        } = node
+        else {
+            return None;
+        };
+        let begin = self.byte_offset(*start)?;
+        let text = &self.lines[start.line][begin..];
+        let len = match style {
+            TScalarStyle::Plain => {
+                Some(value.len()).filter(|len| *len > 0 && text.starts_with(value.as_str()))?
+            }
+            TScalarStyle::SingleQuoted => quoted_len(text, '\'')?,
+            TScalarStyle::DoubleQuoted => quoted_len(text, '"')?,
+            _ => return None,
+        };
+        Some((start.line, begin..begin + len))
+    }
+
+    /// The byte offset of a position in its line.
+    fn byte_offset(&self, position: Position) -> Option<usize> {
+        let line = self.lines.get(position.line)?;
+        line.char_indices()
+            .map(|(offset, _)| offset)
+            .chain(iter::once(line.len()))
+            .nth(position.column)
+    }
+
+    /// The index after the last line of the value of the key on `key_line`, before any blank
+    /// lines or comments which aren't indented past the key.
+    fn value_end(&self, key_line: usize, end: usize, indent: usize) -> usize {
+        (key_line + 1..end)
+            .rev()
+            .find(|index| {
+                let line = &self.lines[*index];
+                let line_indent = line.len() - line.trim_start().len();
+                !line.trim().is_empty() && line_indent > indent
+            })
+            .map_or(key_line + 1, |index| index + 1)
+    }
+
+    /// The index of the first of the comments directly above `index` with the given indent.
+    fn leading_comments_start(&self, index: usize, indent: usize) -> usize {
+        let mut start = index;
+        while start > 0 {
+            let line = &self.lines[start - 1];
+            let is_comment = line.trim_start().starts_with('#');
+            if !is_comment || line.len() - line.trim_start().len() != indent {
+                break;
+            }
+            start -= 1;
+        }
+        start
+    }
+}
+
+impl Display for SupergraphConfigDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.lines.join(self.line_break))?;
+        if self.trailing_line_break && !self.lines.is_empty() {
+            write!(f, "{}", self.line_break)?;
+        }
+        Ok(())
+    }
+}
+
+impl Node {
+    fn start(&self) -> Position {
+        match self {
+            Node::Scalar { start, .. } | Node::Mapping { start, .. } | Node::Other { start } => {
+                *start
+            }
+        }
+    }
+}
+
+/// Builds the [`Node`] tree of the first document from the parser's events.
+struct TreeBuilder {
+    /// The document, to tell flow mappings from block ones.
+    source: Vec<char>,
+    /// The collections being built, with a mapping's key while its value is.
+    stack: Vec<(Node, Option<Node>)>,
+    root: Option<Node>,
+}
+
+impl TreeBuilder {
+    fn add(&mut self, node: Node) {
+        match self.stack.last_mut() {
+            None => {
+                self.root.get_or_insert(node);
+            }
+            Some((Node::Mapping { entries, .. }, pending_key)) => match pending_key.take() {
+                Some(key) => {
+                    if let Node::Scalar { value, .. } = &key {
+                        entries.push(Entry {
+                            name: value.clone(),
+                            key,
+                            value: node,
+                        });
+                    }
+                }
+                None => *pending_key = Some(node),
+            },
+            Some(_) => {}
+        }
+    }
+}
+
+impl MarkedEventReceiver for TreeBuilder {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        let start = Position {
+            line: mark.line().saturating_sub(1),
+            column: mark.col(),
+        };
+        match event {
+            Event::Scalar(value, style, ..) => self.add(Node::Scalar {
+                value,
+                style,
+                start,
+            }),
+            Event::Alias(_) => self.add(Node::Other { start }),
+            Event::MappingStart(_) => {
+                let mapping = Node::Mapping {
+                    flow: self.source.get(mark.index()) == Some(&'{'),
+                    start,
+                    entries: Vec::new(),
+                };
+                self.stack.push((mapping, None));
+            }
+            Event::SequenceStart(_) => self.stack.push((Node::Other { start }, None)),
+            Event::MappingEnd | Event::SequenceEnd => {
+                if let Some((node, _)) = self.stack.pop() {
+                    self.add(node);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn not_editable(key: &str) -> ConfigError {
+    ConfigError::InvalidConfiguration {
+        message: format!(
+            "{key} must be written on one line, and not as a block scalar, to be edited"
+        ),
+    }
+}
+
+/// The length of the quoted scalar `text` starts with, or `None` if it doesn't end on the line.
+fn quoted_len(text: &str, quote: char) -> Option<usize> {
+    let mut chars = text.char_indices().skip(1);
+    while let Some((offset, c)) = chars.next() {
+        if c == '\\' && quote == '"' {
+            chars.next();
+        } else if c == quote {
+            // Single quotes are escaped by doubling them
+            if quote == '\'' && text[offset + 1..].starts_with('\'') {
+                chars.next();
+            } else {
+                return Some(offset + 1);
+            }
+        }
+    }
+    None
+}
+
+/// Writes a string as a scalar in the given style, or double-quoted if it can't be.
+fn scalar(value: &str, style: TScalarStyle) -> String {
+    match style {
+        TScalarStyle::Plain if is_plain(value) => value.to_string(),
+        TScalarStyle::SingleQuoted if !value.chars().any(char::is_control) => {
+            format!("'{}'", value.replace('\'', "''"))
+        }
+        _ => double_quoted(value),
+    }
+}
+
+/// Like [`scalar`], but `federation_version` can be written as a plain number.
+fn version_scalar(value: &str, style: TScalarStyle) -> String {
+    if matches!(style, TScalarStyle::Plain) && value.parse::<u64>().is_ok() {
+        value.to_string()
+    } else {
+        scalar(value, style)
+    }
+}
+
+/// Whether a string reads back as the same string when it's written without quotes.
+fn is_plain(value: &str) -> bool {
+    value.trim() == value
+        && !value.chars().any(char::is_control)
+        && YamlLoader::load_from_str(value)
+            .is_ok_and(|documents| documents == [Yaml::String(value.to_string())])
+}
+
+fn double_quoted(value: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", u32::from(c))),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Writes a serialized value as lines of block YAML indented by `step`, leaving out null fields.
+fn block_lines(value: &Value, step: usize) -> Vec<String> {
+    let nested = |value: &Value| {
+        block_lines(value, step)
+            .into_iter()
+            .map(move |line| format!("{}{line}", " ".repeat(step)))
+    };
+    match value {
+        Value::Mapping(mapping) => mapping
+            .iter()
+            .filter(|(_, value)| !value.is_null())
+            .flat_map(|(key, value)| {
+                let key = inline(key);
+                if is_block(value) {
+                    iter::once(format!("{key}:")).chain(nested(value)).collect()
+                } else {
+                    vec![format!("{key}: {}", inline(value))]
+                }
+            })
+            .collect(),
+        Value::Sequence(items) => items
+            .iter()
+            .flat_map(|item| {
+                let lines = if is_block(item) {
+                    block_lines(item, step)
+                } else {
+                    vec![inline(item)]
+                };
+                lines.into_iter().enumerate().map(|(index, line)| {
+                    let marker = if index == 0 { "- " } else { "  " };
+                    format!("{marker}{line}")
+                })
+            })
+            .collect(),
+        _ => vec![inline(value)],
+    }
+}
+
+/// Whether a value is written over several lines, rather than after its key.
+fn is_block(value: &Value) -> bool {
+    match value {
+        Value::Mapping(mapping) => !mapping.is_empty(),
+        Value::Sequence(items) => !items.is_empty(),
+        _ => false,
+    }
+}
+
+/// Writes a scalar, or an empty mapping or sequence, on one line.
+fn inline(value: &Value) -> String {
+    match value {
+        Value::Null => "~".to_string(),
+        Value::Bool(value) => value.to_string(),
+        Value::Number(value) => value.to_string(),
+        Value::String(value) => scalar(value, TScalarStyle::Plain),
+        Value::Sequence(_) => "[]".to_string(),
+        Value::Mapping(_) => "{}".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SchemaSource;
+
+    const YAML: &str = r#"# Shared supergraph
+federation_version: =2.3.0 # pinned
+subgraphs:
+  # Owned by the films team
+  films:
+    routing_url: https://films.example.com # production
+    schema:
+      file: ./films.graphql
+
+  people:
+    schema:
+      # generated by CI
+      file: ./people.graphql
+
+# Shared settings below
+"#;
+
+    #[test]
+    fn it_edits_subgraphs_in_place() {
+        let mut document = SupergraphConfigDocument::parse(YAML).unwrap();
+        assert_eq!(document.subgraph_names().unwrap(), ["films", "people"]);
+
+        document
+            .set_routing_url("films", Some("http://localhost:4001"))
+            .unwrap();
+        document
+            .set_routing_url("people", Some("https://people.example.com"))
+            .unwrap();
+        document
+            .set_subgraph(
+                "reviews",
+                &SubgraphConfig {
+                    routing_url: None,
+                    schema: SchemaSource::File {
+                        file: "./reviews.graphql".into(),
+                    },
+                },
+            )
+            .unwrap();
+        document
+            .set_federation_version(Some(&FederationVersion::LatestFedTwo))
+            .unwrap();
+
+        assert_eq!(
+            document.to_string(),
+            r#"# Shared supergraph
+federation_version: 2 # pinned
+subgraphs:
+  # Owned by the films team
+  films:
+    routing_url: http://localhost:4001 # production
+    schema:
+      file: ./films.graphql
+
+  people:
+    routing_url: https://people.example.com
+    schema:
+      # generated by CI
+      file: ./people.graphql
+
+  reviews:
+    schema:
+      file: ./reviews.graphql
+
+# Shared settings below
+"#
+        );
+        assert_eq!(document.config().unwrap().into_iter().count(), 3);
+
+        assert!(document.remove_subgraph("films").unwrap());
+        assert!(!document.remove_subgraph("films").unwrap());
+        assert!(!document.to_string().contains("films"));
+        assert_eq!(document.subgraph_names().unwrap(), ["people", "reviews"]);
+    }
+
+    #[test]
+    fn it_replaces_subgraphs_and_fills_empty_configs() {
+        let mut document = SupergraphConfigDocument::parse("subgraphs: {} # none yet\r\n").unwrap();
+        let subgraph = SubgraphConfig {
+            routing_url: Some("http://localhost:4001".to_string()),
+            schema: SchemaSource::Sdl {
+                sdl: "type Query { a: Int }".to_string(),
+            },
+        };
+        document.set_subgraph("a", &subgraph).unwrap();
+        document.set_subgraph("a", &subgraph).unwrap();
+        document.set_federation_version(None).unwrap();
+        assert_eq!(
+            document.to_string(),
+            "subgraphs: # none yet\r\n  a:\r\n    routing_url: http://localhost:4001\r\n    schema:\r\n      sdl: \"type Query { a: Int }\"\r\n"
+        );
+
+        assert!(document.remove_subgraph("a").unwrap());
+        assert_eq!(document.to_string(), "subgraphs: {} # none yet\r\n");
+    }
+
+    #[test]
+    fn it_refuses_flow_mappings() {
+        let mut document =
+            SupergraphConfigDocument::parse("subgraphs: { a: { schema: { file: a.graphql } } }\n")
+                .unwrap();
+        assert!(matches!(
+            document.remove_subgraph("a"),
+            Err(ConfigError::InvalidConfiguration { .. })
+        ));
+
+        let mut document =
+            SupergraphConfigDocument::parse("subgraphs:\n  a: { schema: { file: a.graphql } }\n")
+                .unwrap();
+        assert!(matches!(
+            document.set_routing_url("a", Some("http://localhost:4001")),
+            Err(ConfigError::InvalidConfiguration { .. })
+        ));
+        document
+            .set_subgraph(
+                "a",
+                &SubgraphConfig {
+                    routing_url: None,
+                    schema: SchemaSource::File {
+                        file: "b.graphql".into(),
+                    },
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            document.to_string(),
+            "subgraphs:\n  a:\n    schema:\n      file: b.graphql\n"
+        );
+    }
+
+    #[test]
+    fn it_keeps_quotes_and_comments_around_hashes() {
+        let mut document = SupergraphConfigDocument::parse(
+            r#"federation_version: '=2.3.0' # "pinned" # for now
+subgraphs:
+  films:
+    routing_url: "https://films.example.com/#graphql" # the # is part of the URL
+    schema:
+      file: './films #1.graphql' # with a ' #'
+  people:
+    routing_url: https://people.example.com # plain
+    schema:
+      file: ./people.graphql
+"#,
+        )
+        .unwrap();
+
+        document
+            .set_routing_url("films", Some("https://films.example.com/#v2"))
+            .unwrap();
+        document
+            .set_routing_url("people", Some("https://people.example.com/ #v2"))
+            .unwrap();
+        document
+            .set_federation_version(Some(&FederationVersion::ExactFedTwo(
+                "2.4.0".parse().unwrap(),
+            )))
+            .unwrap();
+        assert_eq!(
+            document.to_string(),
+            r#"federation_version: '=2.4.0' # "pinned" # for now
+subgraphs:
+  films:
+    routing_url: "https://films.example.com/#v2" # the # is part of the URL
+    schema:
+      file: './films #1.graphql' # with a ' #'
+  people:
+    routing_url: "https://people.example.com/ #v2" # plain
+    schema:
+      file: ./people.graphql
+"#
+        );
+
+        document.set_routing_url("films", None).unwrap();
+        document.set_federation_version(None).unwrap();
+        assert_eq!(
+            document.to_string(),
+            r#"subgraphs:
+  films:
+    schema:
+      file: './films #1.graphql' # with a ' #'
+  people:
+    routing_url: "https://people.example.com/ #v2" # plain
+    schema:
+      file: ./people.graphql
+"#
+        );
+    }
+
+    #[rstest::rstest]
+    #[case::folded("routing_url: >-\n      https://films.example.com\n")]
+    #[case::literal("routing_url: |-\n      https://films.example.com\n")]
+    #[case::multi_line_plain("routing_url: https://films.example.com/\n      graphql\n")]
+    #[case::multi_line_quoted("routing_url: \"https://films.example.com/\n      graphql\"\n")]
+    #[case::alias("routing_url: *url\n")]
+    fn it_refuses_to_edit_scalars_it_cant_replace(#[case] routing_url: &str) {
+        let yaml = format!(
+            "x-url: &url https://films.example.com\nsubgraphs:\n  films:\n    {routing_url}    schema:\n      sdl: |\n        type Query {{ a: Int }}\n"
+        );
+        let mut document = SupergraphConfigDocument::parse(&yaml).unwrap();
+        for routing_url in [Some("http://localhost:4001"), None] {
+            assert!(matches!(
+                document.set_routing_url("films", routing_url),
+                Err(ConfigError::InvalidConfiguration { .. })
+            ));
+            assert_eq!(document.to_string(), yaml);
+        }
+    }
+
+    #[test]
+    fn it_undoes_edits_which_change_other_values() {
+        let yaml = "subgraphs:\n  films:\n    routing_url: &url https://films.example.com\n    schema:\n      file: ./films.graphql\n  people:\n    routing_url: *url\n    schema:\n      file: ./people.graphql\n";
+        let mut document = SupergraphConfigDocument::parse(yaml).unwrap();
+        assert!(matches!(
+            document.set_routing_url("films", Some("http://localhost:4001")),
+            Err(ConfigError::InvalidConfiguration { message }) if message.contains("wasn't meant to")
+        ));
+        assert_eq!(document.to_string(), yaml);
+    }
+}
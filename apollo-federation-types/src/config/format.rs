@@ -0,0 +1,75 @@
+use std::path::Path;
+
+/// A format a supergraph config can be written in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConfigFormat {
+    Yaml,
+    Json,
+    Toml,
+}
+
+impl ConfigFormat {
+    /// The format of a file with a `.yaml`, `.yml`, `.json` or `.toml` extension.
+    pub fn from_extension(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "yaml" | "yml" => Some(ConfigFormat::Yaml),
+            "json" => Some(ConfigFormat::Json),
+            "toml" => Some(ConfigFormat::Toml),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format of a config from its contents: JSON if it's an object, TOML if it
+    /// starts with a table header or a `key = value` pair, and YAML otherwise.
+    pub fn detect(contents: &str) -> Self {
+        let first_line = contents
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .unwrap_or_default();
+        if first_line.starts_with('{') {
+            return ConfigFormat::Json;
+        }
+        let is_toml_key = first_line.split_once('=').is_some_and(|(key, _)| {
+            let key = key.trim();
+            !key.is_empty()
+                && key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '"'))
+        });
+        if first_line.starts_with('[') || is_toml_key {
+            ConfigFormat::Toml
+        } else {
+            ConfigFormat::Yaml
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rstest::rstest]
+    #[case("---\nsubgraphs: {}\n", ConfigFormat::Yaml)]
+    #[case("# comment\nfederation_version: =2.3.0\n", ConfigFormat::Yaml)]
+    #[case("  {\n  \"subgraphs\": {}\n}", ConfigFormat::Json)]
+    #[case("federation_version = \"=2.3.0\"\n", ConfigFormat::Toml)]
+    #[case(
+        "# comment\n\n[subgraphs.films]\nrouting_url = \"http://films\"\n",
+        ConfigFormat::Toml
+    )]
+    #[case("", ConfigFormat::Yaml)]
+    fn test_detect(#[case] contents: &str, #[case] expected: ConfigFormat) {
+        assert_eq!(ConfigFormat::detect(contents), expected);
+    }
+
+    #[rstest::rstest]
+    #[case("supergraph.YML", Some(ConfigFormat::Yaml))]
+    #[case("config/supergraph.json", Some(ConfigFormat::Json))]
+    #[case("supergraph.toml", Some(ConfigFormat::Toml))]
+    #[case("supergraph", None)]
+    fn test_from_extension(#[case] path: &str, #[case] expected: Option<ConfigFormat>) {
+        assert_eq!(ConfigFormat::from_extension(Path::new(path)), expected);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::config::{ConfigError, ConfigResult};
+
+const PREFIX: &str = "${env.";
+const DEFAULT_SEPARATOR: &str = ":-";
+
+/// Expands every `${env.NAME}` and `${env.NAME:-default}` in `value`, where the default is used
+/// if the variable is unset or empty. `key_path` is the location of `value` in the config, for
+/// errors.
+pub(crate) fn interpolate(
+    value: &str,
+    key_path: &str,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> ConfigResult<String> {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find(PREFIX) {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+        let expression = &rest[start + PREFIX.len()..start + end];
+        let (name, default) = match expression.split_once(DEFAULT_SEPARATOR) {
+            Some((name, default)) => (name, Some(default)),
+            None => (expression, None),
+        };
+        match (lookup(name), default) {
+            (Some(value), Some(default)) if value.is_empty() => result.push_str(default),
+            (Some(value), _) => result.push_str(&value),
+            (None, Some(default)) => result.push_str(default),
+            (None, None) => {
+                return Err(ConfigError::MissingEnvironmentVariable {
+                    name: name.to_string(),
+                    key_path: key_path.to_string(),
+                })
+            }
+        }
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "HOST" => Some("example.com".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    #[rstest::rstest]
+    #[case("https://${env.HOST}/graphql", "https://example.com/graphql")]
+    #[case("${env.MISSING:-localhost}:${env.PORT:-4000}", "localhost:4000")]
+    #[case("${env.EMPTY:-default}", "default")]
+    #[case("[${env.EMPTY}]", "[]")]
+    #[case("${env.HOST:-}", "example.com")]
+    #[case("no variables", "no variables")]
+    #[case("${other.HOST} ${env.HOST", "${other.HOST} ${env.HOST")]
+    fn test_interpolate(#[case] value: &str, #[case] expected: &str) {
+        assert_eq!(interpolate(value, "key", &lookup).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_interpolate_missing_variable() {
+        let error =
+            interpolate("${env.MISSING}", "subgraphs.films.routing_url", &lookup).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Environment variable \"MISSING\" used in subgraphs.films.routing_url is not set."
+        );
+        assert_eq!(error.code().as_deref(), Some("CONFIG_ENV_VAR_MISSING"));
+    }
+}
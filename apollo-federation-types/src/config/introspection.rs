@@ -0,0 +1,850 @@
+use std::{collections::HashMap, fmt::Write, thread, time::Duration};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::config::{ResolveError, SchemaResolver};
+
+const SERVICE_QUERY: &str = "query SubgraphIntrospectQuery { _service { sdl } }";
+
+/// The standard introspection query. Servers implementing a spec older than October 2021 (or the
+/// draft adding `isOneOf`) reject some of its fields, so they're left out when `legacy` is set.
+fn introspection_query(legacy: bool) -> String {
+    let (include_deprecated, directive_fields, type_fields, input_value_fields) = if legacy {
+        ("", "", "", "")
+    } else {
+        (
+            "(includeDeprecated: true)",
+            "\n      isRepeatable",
+            "\n  specifiedByURL\n  isOneOf",
+            "\n  isDeprecated\n  deprecationReason",
+        )
+    };
+    format!(
+        r#"query IntrospectionQuery {{
+  __schema {{
+    queryType {{ name }}
+    mutationType {{ name }}
+    subscriptionType {{ name }}
+    types {{ ...FullType }}
+    directives {{
+      name
+      description{directive_fields}
+      locations
+      args{include_deprecated} {{ ...InputValue }}
+    }}
+  }}
+}}
+
+fragment FullType on __Type {{
+  kind
+  name
+  description{type_fields}
+  fields(includeDeprecated: true) {{
+    name
+    description
+    args{include_deprecated} {{ ...InputValue }}
+    type {{ ...TypeRef }}
+    isDeprecated
+    deprecationReason
+  }}
+  inputFields{include_deprecated} {{ ...InputValue }}
+  interfaces {{ ...TypeRef }}
+  enumValues(includeDeprecated: true) {{
+    name
+    description
+    isDeprecated
+    deprecationReason
+  }}
+  possibleTypes {{ ...TypeRef }}
+}}
+
+fragment InputValue on __InputValue {{
+  name
+  description
+  type {{ ...TypeRef }}
+  defaultValue{input_value_fields}
+}}
+
+{TYPE_REF_FRAGMENT}"#
+    )
+}
+
+const TYPE_REF_FRAGMENT: &str = r#"fragment TypeRef on __Type {
+  kind
+  name
+  ofType {
+    kind
+    name
+    ofType {
+      kind
+      name
+      ofType {
+        kind
+        name
+        ofType {
+          kind
+          name
+          ofType {
+            kind
+            name
+            ofType {
+              kind
+              name
+              ofType { kind name }
+            }
+          }
+        }
+      }
+    }
+  }
+}"#;
+
+const BUILT_IN_SCALARS: &[&str] = &["String", "Int", "Float", "Boolean", "ID"];
+const BUILT_IN_DIRECTIVES: &[&str] = &["include", "skip", "deprecated", "specifiedBy", "oneOf"];
+const DEFAULT_DEPRECATION_REASON: &str = "No longer supported";
+
+/// A [`SchemaResolver`] which introspects subgraphs over HTTP.
+///
+/// Subgraphs are asked for their SDL with the federation `_service { sdl }` query, which keeps
+/// federation directives. Services which don't support it are introspected with the standard
+/// introspection query instead, which loses directives other than `@deprecated`, `@specifiedBy`
+/// and `@oneOf`. Files are read like
+/// [`FileSchemaResolver`](crate::config::FileSchemaResolver), and registry subgraphs aren't
+/// supported.
+#[derive(Clone, Debug)]
+pub struct IntrospectionClient {
+    agent: ureq::Agent,
+    retries: usize,
+    retry_delay: Duration,
+    max_retry_delay: Duration,
+    max_retry_wait: Duration,
+}
+
+impl Default for IntrospectionClient {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(30))
+    }
+}
+
+impl IntrospectionClient {
+    /// Creates a client whose requests fail after `timeout`, retrying failed requests twice.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new().timeout(timeout).build(),
+            retries: 2,
+            retry_delay: Duration::from_millis(500),
+            max_retry_delay: Duration::from_secs(5),
+            max_retry_wait: Duration::from_secs(30),
+        }
+    }
+
+    /// How many times to retry a request which timed out, couldn't connect, or got a 429 or 5xx
+    /// response.
+    pub fn with_retries(mut self, retries: usize) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// How long to wait before retrying; the delay doubles with each retry, up to
+    /// [`IntrospectionClient::with_max_retry_delay`].
+    pub fn with_retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// The longest to wait before a single retry, which is 5 seconds by default.
+    pub fn with_max_retry_delay(mut self, max_retry_delay: Duration) -> Self {
+        self.max_retry_delay = max_retry_delay;
+        self
+    }
+
+    /// The longest to wait between retries of a request in total, which is 30 seconds by default.
+    /// Requests aren't retried once waiting for the next retry would take longer.
+    pub fn with_max_retry_wait(mut self, max_retry_wait: Duration) -> Self {
+        self.max_retry_wait = max_retry_wait;
+        self
+    }
+
+    /// Posts a GraphQL query, returning the response body.
+    fn post(
+        &self,
+        subgraph_url: &Url,
+        headers: &HashMap<String, String>,
+        query: &str,
+    ) -> Result<Value, ResolveError> {
+        let body = json!({ "query": query }).to_string();
+        let mut delay = self.retry_delay.min(self.max_retry_delay);
+        let mut waited = Duration::ZERO;
+        let mut attempt = 0;
+        loop {
+            let mut request = self
+                .agent
+                .request_url("POST", subgraph_url)
+                .set("Content-Type", "application/json");
+            for (name, value) in headers {
+                request = request.set(name, value);
+            }
+            let response = match request.send_string(&body) {
+                Ok(response) => Ok(response),
+                // GraphQL servers often respond to invalid queries with a 400 and the errors
+                Err(ureq::Error::Status(status, response)) if !is_retryable(status) => Ok(response),
+                Err(err) => Err(err),
+            };
+            match response {
+                Ok(response) => {
+                    let status = response.status();
+                    let text = response.into_string()?;
+                    return serde_json::from_str(&text).map_err(|err| {
+                        format!(
+                            "{subgraph_url} responded with HTTP {status} and invalid JSON: {err}"
+                        )
+                        .into()
+                    });
+                }
+                Err(err) if attempt < self.retries && waited + delay <= self.max_retry_wait => {
+                    log::debug!("retrying introspection of {subgraph_url}: {err}");
+                    attempt += 1;
+                    thread::sleep(delay);
+                    waited += delay;
+                    delay = (delay * 2).min(self.max_retry_delay);
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+}
+
+impl SchemaResolver for IntrospectionClient {
+    fn introspect(
+        &self,
+        subgraph_url: &Url,
+        introspection_headers: &HashMap<String, String>,
+    ) -> Result<String, ResolveError> {
+        let response = self.post(subgraph_url, introspection_headers, SERVICE_QUERY)?;
+        if let Some(sdl) = response["data"]["_service"]["sdl"].as_str() {
+            return Ok(sdl.to_string());
+        }
+        log::debug!("{subgraph_url} isn't a federated subgraph, falling back to introspection");
+
+        let query = introspection_query(false);
+        let mut response = self.post(subgraph_url, introspection_headers, &query)?;
+        if response["data"]["__schema"].is_null() {
+            log::debug!(
+                "{subgraph_url} rejected the introspection query ({}), retrying without newer fields",
+                error_messages(&response)
+            );
+            let query = introspection_query(true);
+            response = self.post(subgraph_url, introspection_headers, &query)?;
+        }
+        match response["data"].get_mut("__schema").map(Value::take) {
+            Some(schema) => Ok(introspection_to_sdl(&serde_json::from_value(schema)?)),
+            None => Err(format!(
+                "could not introspect {subgraph_url}: {}",
+                error_messages(&response)
+            )
+            .into()),
+        }
+    }
+}
+
+fn is_retryable(status: u16) -> bool {
+    status == 429 || status >= 500
+}
+
+fn error_messages(response: &Value) -> String {
+    let messages: Vec<&str> = response["errors"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|error| error["message"].as_str())
+        .collect();
+    if messages.is_empty() {
+        "the response has no data".to_string()
+    } else {
+        messages.join(", ")
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IntrospectionSchema {
+    query_type: Option<NamedType>,
+    mutation_type: Option<NamedType>,
+    subscription_type: Option<NamedType>,
+    types: Vec<FullType>,
+    #[serde(default)]
+    directives: Vec<Directive>,
+}
+
+#[derive(Deserialize)]
+struct NamedType {
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FullType {
+    kind: String,
+    name: String,
+    description: Option<String>,
+    #[serde(default, rename = "specifiedByURL")]
+    specified_by_url: Option<String>,
+    #[serde(default)]
+    is_one_of: Option<bool>,
+    fields: Option<Vec<Field>>,
+    input_fields: Option<Vec<InputValue>>,
+    interfaces: Option<Vec<TypeRef>>,
+    enum_values: Option<Vec<EnumValue>>,
+    possible_types: Option<Vec<TypeRef>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Field {
+    name: String,
+    description: Option<String>,
+    #[serde(default)]
+    args: Vec<InputValue>,
+    #[serde(rename = "type")]
+    ty: TypeRef,
+    #[serde(default)]
+    is_deprecated: bool,
+    deprecation_reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InputValue {
+    name: String,
+    description: Option<String>,
+    #[serde(rename = "type")]
+    ty: TypeRef,
+    default_value: Option<String>,
+    #[serde(default)]
+    is_deprecated: bool,
+    #[serde(default)]
+    deprecation_reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EnumValue {
+    name: String,
+    description: Option<String>,
+    #[serde(default)]
+    is_deprecated: bool,
+    deprecation_reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Directive {
+    name: String,
+    description: Option<String>,
+    #[serde(default)]
+    is_repeatable: bool,
+    locations: Vec<String>,
+    #[serde(default)]
+    args: Vec<InputValue>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TypeRef {
+    kind: String,
+    name: Option<String>,
+    of_type: Option<Box<TypeRef>>,
+}
+
+impl TypeRef {
+    fn to_sdl(&self) -> String {
+        let of_type = || {
+            self.of_type
+                .as_ref()
+                .map(|ty| ty.to_sdl())
+                .unwrap_or_default()
+        };
+        match self.kind.as_str() {
+            "NON_NULL" => format!("{}!", of_type()),
+            "LIST" => format!("[{}]", of_type()),
+            _ => self.name.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// Prints the SDL of a standard introspection result, leaving out built-in definitions.
+fn introspection_to_sdl(schema: &IntrospectionSchema) -> String {
+    let mut definitions = Vec::new();
+
+    let root_operations = [
+        ("query", &schema.query_type, "Query"),
+        ("mutation", &schema.mutation_type, "Mutation"),
+        ("subscription", &schema.subscription_type, "Subscription"),
+    ];
+    if root_operations
+        .iter()
+        .any(|(_, ty, default)| ty.as_ref().is_some_and(|ty| ty.name != *default))
+    {
+        let mut definition = "schema {\n".to_string();
+        for (operation, ty, _) in root_operations {
+            if let Some(ty) = ty {
+                let _ = writeln!(definition, "  {operation}: {}", ty.name);
+            }
+        }
+        definition.push('}');
+        definitions.push(definition);
+    }
+
+    for directive in &schema.directives {
+        if BUILT_IN_DIRECTIVES.contains(&directive.name.as_str()) {
+            continue;
+        }
+        let mut definition = description(directive.description.as_deref(), "");
+        let _ = write!(
+            definition,
+            "directive @{}{}{} on {}",
+            directive.name,
+            arguments(&directive.args, ""),
+            if directive.is_repeatable {
+                " repeatable"
+            } else {
+                ""
+            },
+            directive.locations.join(" | ")
+        );
+        definitions.push(definition);
+    }
+
+    for ty in &schema.types {
+        if ty.name.starts_with("__")
+            || (ty.kind == "SCALAR" && BUILT_IN_SCALARS.contains(&ty.name.as_str()))
+        {
+            continue;
+        }
+        let mut definition = description(ty.description.as_deref(), "");
+        match ty.kind.as_str() {
+            "SCALAR" => {
+                let _ = write!(definition, "scalar {}", ty.name);
+                if let Some(url) = &ty.specified_by_url {
+                    let _ = write!(
+                        definition,
+                        " @specifiedBy(url: {})",
+                        Value::from(url.as_str())
+                    );
+                }
+            }
+            "OBJECT" | "INTERFACE" => {
+                let keyword = if ty.kind == "OBJECT" {
+                    "type"
+                } else {
+                    "interface"
+                };
+                let _ = write!(definition, "{keyword} {}", ty.name);
+                let interfaces: Vec<String> = ty
+                    .interfaces
+                    .iter()
+                    .flatten()
+                    .map(TypeRef::to_sdl)
+                    .collect();
+                if !interfaces.is_empty() {
+                    let _ = write!(definition, " implements {}", interfaces.join(" & "));
+                }
+                definition.push_str(" {\n");
+                for field in ty.fields.iter().flatten() {
+                    definition.push_str(&description(field.description.as_deref(), "  "));
+                    let _ = writeln!(
+                        definition,
+                        "  {}{}: {}{}",
+                        field.name,
+                        arguments(&field.args, "  "),
+                        field.ty.to_sdl(),
+                        deprecated(field.is_deprecated, field.deprecation_reason.as_deref())
+                    );
+                }
+                definition.push('}');
+            }
+            "UNION" => {
+                let members: Vec<String> = ty
+                    .possible_types
+                    .iter()
+                    .flatten()
+                    .map(TypeRef::to_sdl)
+                    .collect();
+                let _ = write!(definition, "union {} = {}", ty.name, members.join(" | "));
+            }
+            "ENUM" => {
+                let _ = writeln!(definition, "enum {} {{", ty.name);
+                for value in ty.enum_values.iter().flatten() {
+                    definition.push_str(&description(value.description.as_deref(), "  "));
+                    let _ = writeln!(
+                        definition,
+                        "  {}{}",
+                        value.name,
+                        deprecated(value.is_deprecated, value.deprecation_reason.as_deref())
+                    );
+                }
+                definition.push('}');
+            }
+            "INPUT_OBJECT" => {
+                let one_of = if ty.is_one_of == Some(true) {
+                    " @oneOf"
+                } else {
+                    ""
+                };
+                let _ = writeln!(definition, "input {}{one_of} {{", ty.name);
+                for field in ty.input_fields.iter().flatten() {
+                    definition.push_str(&description(field.description.as_deref(), "  "));
+                    let _ = writeln!(definition, "  {}", input_value(field));
+                }
+                definition.push('}');
+            }
+            _ => continue,
+        }
+        definitions.push(definition);
+    }
+
+    definitions.join("\n\n") + "\n"
+}
+
+fn description(description: Option<&str>, indent: &str) -> String {
+    match description.filter(|description| !description.is_empty()) {
+        Some(description) => {
+            let description = description.replace("\"\"\"", "\\\"\"\"");
+            format!("{indent}\"\"\"\n{indent}{description}\n{indent}\"\"\"\n")
+        }
+        None => String::new(),
+    }
+}
+
+fn arguments(args: &[InputValue], indent: &str) -> String {
+    if args.is_empty() {
+        return String::new();
+    }
+    if args.iter().all(|arg| arg.description.is_none()) {
+        let args: Vec<String> = args.iter().map(input_value).collect();
+        return format!("({})", args.join(", "));
+    }
+    let mut arguments = "(\n".to_string();
+    let arg_indent = format!("{indent}  ");
+    for arg in args {
+        arguments.push_str(&description(arg.description.as_deref(), &arg_indent));
+        let _ = writeln!(arguments, "{arg_indent}{}", input_value(arg));
+    }
+    arguments.push_str(indent);
+    arguments.push(')');
+    arguments
+}
+
+fn input_value(value: &InputValue) -> String {
+    let deprecated = deprecated(value.is_deprecated, value.deprecation_reason.as_deref());
+    match &value.default_value {
+        Some(default_value) => format!(
+            "{}: {} = {default_value}{deprecated}",
+            value.name,
+            value.ty.to_sdl()
+        ),
+        None => format!("{}: {}{deprecated}", value.name, value.ty.to_sdl()),
+    }
+}
+
+fn deprecated(is_deprecated: bool, reason: Option<&str>) -> String {
+    match reason {
+        _ if !is_deprecated => String::new(),
+        Some(reason) if reason != DEFAULT_DEPRECATION_REASON => {
+            format!(" @deprecated(reason: {})", Value::from(reason))
+        }
+        _ => " @deprecated".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread::JoinHandle,
+    };
+
+    use super::*;
+
+    /// Serves one canned response per connection, returning the requests it got.
+    fn serve(responses: Vec<(u16, Value)>) -> (Url, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/graphql", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(length) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = length.trim().parse().unwrap();
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+                let mut body_bytes = vec![0; content_length];
+                reader.read_exact(&mut body_bytes).unwrap();
+                request.push_str(&String::from_utf8(body_bytes).unwrap());
+                requests.push(request);
+
+                let body = body.to_string();
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} Status\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+            requests
+        });
+        (url.parse().unwrap(), handle)
+    }
+
+    fn client() -> IntrospectionClient {
+        IntrospectionClient::new(Duration::from_secs(5)).with_retry_delay(Duration::ZERO)
+    }
+
+    #[test]
+    fn it_fetches_federated_sdl_with_headers() {
+        let sdl = "type Query { a: Int } extend schema @link(url: \"https://specs.apollo.dev/federation/v2.3\")";
+        let (url, server) = serve(vec![
+            (503, json!({})),
+            (200, json!({ "data": { "_service": { "sdl": sdl } } })),
+        ]);
+        let headers = HashMap::from([("Authorization".to_string(), "Bearer secret".to_string())]);
+
+        assert_eq!(client().introspect(&url, &headers).unwrap(), sdl);
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1].contains("Authorization: Bearer secret"));
+        assert!(requests[1].contains("_service"));
+    }
+
+    #[test]
+    fn it_falls_back_to_standard_introspection() {
+        let (url, server) = serve(vec![
+            (
+                400,
+                json!({ "errors": [{ "message": "Cannot query field \"_service\" on type \"Query\"." }] }),
+            ),
+            (
+                200,
+                json!({ "data": { "__schema": {
+                    "queryType": { "name": "Query" },
+                    "mutationType": null,
+                    "subscriptionType": null,
+                    "types": [
+                        {
+                            "kind": "OBJECT",
+                            "name": "Query",
+                            "description": null,
+                            "fields": [{
+                                "name": "films",
+                                "description": "All films",
+                                "args": [{
+                                    "name": "first",
+                                    "description": null,
+                                    "type": { "kind": "SCALAR", "name": "Int", "ofType": null },
+                                    "defaultValue": "10",
+                                }],
+                                "type": {
+                                    "kind": "NON_NULL",
+                                    "name": null,
+                                    "ofType": {
+                                        "kind": "LIST",
+                                        "name": null,
+                                        "ofType": { "kind": "OBJECT", "name": "Film", "ofType": null },
+                                    },
+                                },
+                                "isDeprecated": false,
+                                "deprecationReason": null,
+                            }],
+                            "inputFields": null,
+                            "interfaces": [],
+                            "enumValues": null,
+                            "possibleTypes": null,
+                        },
+                        {
+                            "kind": "ENUM",
+                            "name": "Film",
+                            "description": null,
+                            "fields": null,
+                            "inputFields": null,
+                            "interfaces": null,
+                            "enumValues": [
+                                { "name": "NEW_HOPE", "description": null, "isDeprecated": false, "deprecationReason": null },
+                                { "name": "PHANTOM_MENACE", "description": null, "isDeprecated": true, "deprecationReason": "Not canon" },
+                            ],
+                            "possibleTypes": null,
+                        },
+                        {
+                            "kind": "SCALAR",
+                            "name": "String",
+                            "description": null,
+                            "fields": null,
+                            "inputFields": null,
+                            "interfaces": null,
+                            "enumValues": null,
+                            "possibleTypes": null,
+                        },
+                    ],
+                    "directives": [],
+                } } }),
+            ),
+        ]);
+
+        assert_eq!(
+            client().introspect(&url, &HashMap::new()).unwrap(),
+            r#"type Query {
+  """
+  All films
+  """
+  films(first: Int = 10): [Film]!
+}
+
+enum Film {
+  NEW_HOPE
+  PHANTOM_MENACE @deprecated(reason: "Not canon")
+}
+"#
+        );
+        assert!(server.join().unwrap()[1].contains("__schema"));
+    }
+
+    #[test]
+    fn it_gives_up_after_retrying() {
+        let (url, server) = serve(vec![(502, json!({})), (502, json!({}))]);
+
+        let error = client()
+            .with_retries(1)
+            .introspect(&url, &HashMap::new())
+            .unwrap_err();
+        assert!(error.to_string().contains("502"), "{error}");
+        assert_eq!(server.join().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn it_caps_retry_delays() {
+        let (url, server) = serve(vec![(502, json!({})), (502, json!({})), (502, json!({}))]);
+
+        let error = IntrospectionClient::new(Duration::from_secs(5))
+            .with_retries(10)
+            .with_retry_delay(Duration::from_millis(10))
+            .with_max_retry_delay(Duration::from_millis(10))
+            .with_max_retry_wait(Duration::from_millis(25))
+            .introspect(&url, &HashMap::new())
+            .unwrap_err();
+        assert!(error.to_string().contains("502"), "{error}");
+        assert_eq!(server.join().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn it_retries_introspection_without_newer_fields() {
+        let (url, server) = serve(vec![
+            (
+                400,
+                json!({ "errors": [{ "message": "Cannot query field \"_service\"." }] }),
+            ),
+            (
+                400,
+                json!({ "errors": [{ "message": "Cannot query field \"isRepeatable\"." }] }),
+            ),
+            (
+                200,
+                json!({ "data": { "__schema": {
+                    "queryType": { "name": "Query" },
+                    "mutationType": null,
+                    "subscriptionType": null,
+                    "types": [],
+                } } }),
+            ),
+        ]);
+
+        assert_eq!(client().introspect(&url, &HashMap::new()).unwrap(), "\n");
+        let requests = server.join().unwrap();
+        assert!(requests[1].contains("isRepeatable"));
+        assert!(requests[1].contains("includeDeprecated: true) { ...InputValue }"));
+        assert!(!requests[2].contains("isRepeatable"));
+        assert!(!requests[2].contains("specifiedByURL"));
+        assert!(!requests[2].contains("includeDeprecated: true) { ...InputValue }"));
+    }
+
+    #[test]
+    fn it_prints_newer_introspection_fields() {
+        let named = |kind: &str, name: &str| json!({ "kind": kind, "name": name, "ofType": null });
+        let schema: IntrospectionSchema = serde_json::from_value(json!({
+            "queryType": { "name": "Query" },
+            "mutationType": null,
+            "subscriptionType": null,
+            "types": [
+                {
+                    "kind": "SCALAR",
+                    "name": "DateTime",
+                    "description": null,
+                    "specifiedByURL": "https://scalars.graphql.org/andimarek/date-time",
+                },
+                {
+                    "kind": "INPUT_OBJECT",
+                    "name": "FilmBy",
+                    "description": null,
+                    "isOneOf": true,
+                    "inputFields": [
+                        {
+                            "name": "id",
+                            "description": null,
+                            "type": named("SCALAR", "ID"),
+                            "defaultValue": null,
+                            "isDeprecated": false,
+                            "deprecationReason": null,
+                        },
+                        {
+                            "name": "title",
+                            "description": null,
+                            "type": named("SCALAR", "String"),
+                            "defaultValue": null,
+                            "isDeprecated": true,
+                            "deprecationReason": "Use id",
+                        },
+                    ],
+                },
+            ],
+            "directives": [{
+                "name": "tag",
+                "description": null,
+                "isRepeatable": true,
+                "locations": ["FIELD_DEFINITION", "OBJECT"],
+                "args": [{
+                    "name": "label",
+                    "description": null,
+                    "type": named("SCALAR", "String"),
+                    "defaultValue": null,
+                    "isDeprecated": true,
+                    "deprecationReason": "No longer supported",
+                }],
+            }],
+        }))
+        .unwrap();
+
+        assert_eq!(
+            introspection_to_sdl(&schema),
+            r#"directive @tag(label: String @deprecated) repeatable on FIELD_DEFINITION | OBJECT
+
+scalar DateTime @specifiedBy(url: "https://scalars.graphql.org/andimarek/date-time")
+
+input FilmBy @oneOf {
+  id: ID
+  title: String @deprecated(reason: "Use id")
+}
+"#
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use std::fmt::{self, Display};
+
+/// Which value [`SupergraphConfig::merge`](crate::config::SupergraphConfig::merge) keeps when
+/// both configs set a field to different values.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum MergeStrategy {
+    /// The other config's value replaces the existing one.
+    #[default]
+    Override,
+    /// The existing value is kept; the other config only fills in what's missing.
+    KeepExisting,
+    /// Merging fails with a [`ConfigError::MergeConflict`](crate::config::ConfigError::MergeConflict).
+    Reject,
+}
+
+/// What [`SupergraphConfig::merge`](crate::config::SupergraphConfig::merge) changed.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MergeReport {
+    /// Subgraphs which were only in the other config.
+    pub added_subgraphs: Vec<String>,
+    /// Fields the other config replaced.
+    pub overridden: Vec<FieldConflict>,
+    /// Fields the other config set differently, which were kept.
+    pub kept: Vec<FieldConflict>,
+}
+
+/// A field both configs set to different values.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FieldConflict {
+    /// Like `subgraphs.films.routing_url` or `federation_version`.
+    pub key_path: String,
+    pub existing: String,
+    pub other: String,
+}
+
+/// Formats as `key_path: existing -> other`.
+impl Display for FieldConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.key_path, self.existing, self.other)
+    }
+}
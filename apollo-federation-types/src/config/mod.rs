@@ -1,9 +1,26 @@
+pub mod codes;
+mod command;
 mod config_error;
+mod document;
+mod format;
+mod interpolation;
+#[cfg(feature = "introspection")]
+mod introspection;
+mod merge;
+mod resolver;
+mod source_map;
 mod subgraph;
 mod supergraph;
 mod version;
 
-pub use config_error::ConfigError;
+pub use config_error::{CommandFailure, ConfigError};
+pub use document::SupergraphConfigDocument;
+pub use format::ConfigFormat;
+#[cfg(feature = "introspection")]
+pub use introspection::IntrospectionClient;
+pub use merge::{FieldConflict, MergeReport, MergeStrategy};
+pub use resolver::{FileSchemaResolver, ResolveError, SchemaResolver};
+pub use source_map::{SourceFile, SourceMap};
 pub use version::{FederationVersion, PluginVersion, RouterVersion};
 pub type ConfigResult<T> = std::result::Result<T, ConfigError>;
 pub use subgraph::{SchemaSource, SubgraphConfig};
@@ -0,0 +1,244 @@
+use std::{collections::HashMap, error::Error, fs, path::Path};
+
+use url::Url;
+
+/// The error a [`SchemaResolver`] returns when it can't fetch a schema.
+pub type ResolveError = Box<dyn Error + Send + Sync>;
+
+/// Fetches the SDL of subgraphs whose schema isn't written in the supergraph config, for
+/// [`SupergraphConfig::resolve`](crate::config::SupergraphConfig::resolve).
+///
+/// Only reading files is implemented by default; the other sources fail unless the resolver
+/// overrides them.
+pub trait SchemaResolver {
+    /// Reads a [`SchemaSource::File`](crate::config::SchemaSource::File), whose `path` has
+    /// already been resolved against the config directory.
+    fn read_file(&self, path: &Path) -> Result<String, ResolveError> {
+        fs::read_to_string(path)
+            .map_err(|err| format!("could not read \"{}\": {err}", path.display()).into())
+    }
+
+    /// Introspects a [`SchemaSource::SubgraphIntrospection`](crate::config::SchemaSource::SubgraphIntrospection).
+    fn introspect(
+        &self,
+        subgraph_url: &Url,
+        _introspection_headers: &HashMap<String, String>,
+    ) -> Result<String, ResolveError> {
+        Err(format!("introspecting {subgraph_url} is not supported").into())
+    }
+
+    /// Fetches a [`SchemaSource::Subgraph`](crate::config::SchemaSource::Subgraph) from the
+    /// GraphOS registry.
+    fn fetch_subgraph(&self, graphref: &str, subgraph: &str) -> Result<String, ResolveError> {
+        Err(format!("fetching subgraph {subgraph} of {graphref} is not supported").into())
+    }
+}
+
+/// A [`SchemaResolver`] which only reads files.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FileSchemaResolver;
+
+impl SchemaResolver for FileSchemaResolver {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use assert_fs::{prelude::*, TempDir};
+
+    use super::*;
+    use crate::config::{
+        CommandFailure, ConfigError, SchemaSource, SubgraphConfig, SupergraphConfig,
+    };
+
+    struct MockResolver;
+
+    impl SchemaResolver for MockResolver {
+        fn introspect(
+            &self,
+            subgraph_url: &Url,
+            introspection_headers: &HashMap<String, String>,
+        ) -> Result<String, ResolveError> {
+            assert_eq!(introspection_headers["Authorization"], "Bearer secret");
+            Ok(format!("# {subgraph_url}\ntype Query {{ b: Int }}"))
+        }
+    }
+
+    fn subgraph(routing_url: Option<&str>, schema: SchemaSource) -> SubgraphConfig {
+        SubgraphConfig {
+            routing_url: routing_url.map(str::to_string),
+            schema,
+        }
+    }
+
+    #[test]
+    fn it_resolves_schema_sources() {
+        let config_dir = TempDir::new().unwrap();
+        config_dir
+            .child("schemas/a.graphql")
+            .write_str("type Query { a: Int }")
+            .unwrap();
+        let config = SupergraphConfig::new(
+            BTreeMap::from([
+                (
+                    "a".to_string(),
+                    subgraph(
+                        Some("http://a"),
+                        SchemaSource::File {
+                            file: "schemas/a.graphql".into(),
+                        },
+                    ),
+                ),
+                (
+                    "b".to_string(),
+                    subgraph(
+                        None,
+                        SchemaSource::SubgraphIntrospection {
+                            subgraph_url: "http://b/graphql".parse().unwrap(),
+                            introspection_headers: Some(HashMap::from([(
+                                "Authorization".to_string(),
+                                "Bearer secret".to_string(),
+                            )])),
+                        },
+                    ),
+                ),
+            ]),
+            None,
+        );
+
+        let resolved = config.resolve(&MockResolver, config_dir.path()).unwrap();
+        let definitions = resolved.get_subgraph_definitions().unwrap();
+        assert_eq!(definitions[0].sdl, "type Query { a: Int }");
+        assert_eq!(definitions[1].url, "http://b/graphql");
+        assert_eq!(
+            definitions[1].sdl,
+            "# http://b/graphql\ntype Query { b: Int }"
+        );
+    }
+
+    #[test]
+    fn it_concatenates_files() {
+        let config_dir = TempDir::new().unwrap();
+        config_dir
+            .child("products/schema.graphql")
+            .write_str("type Query {\n  products: [Product]\n}")
+            .unwrap();
+        config_dir
+            .child("products/types/product.graphql")
+            .write_str("type Product {\n  id: ID!\n}\n")
+            .unwrap();
+        let config = SupergraphConfig::new(
+            BTreeMap::from([(
+                "products".to_string(),
+                subgraph(
+                    Some("http://products"),
+                    SchemaSource::Files {
+                        files: vec![
+                            "products/schema.graphql".to_string(),
+                            "products/types/*.graphql".to_string(),
+                        ],
+                    },
+                ),
+            )]),
+            None,
+        );
+
+        let (resolved, source_maps) = config
+            .resolve_with_source_maps(&FileSchemaResolver, config_dir.path())
+            .unwrap();
+        assert_eq!(
+            resolved.get_subgraph_definitions().unwrap()[0].sdl,
+            "type Query {\n  products: [Product]\n}\ntype Product {\n  id: ID!\n}\n"
+        );
+        let (path, line) = source_maps["products"].locate(5).unwrap();
+        assert_eq!(
+            path,
+            config_dir.path().join("products/types/product.graphql")
+        );
+        assert_eq!(line, 2);
+    }
+
+    #[test]
+    fn it_reports_each_unresolved_subgraph() {
+        let config = SupergraphConfig::new(
+            BTreeMap::from([
+                (
+                    "a".to_string(),
+                    subgraph(
+                        Some("http://a"),
+                        SchemaSource::File {
+                            file: "missing.graphql".into(),
+                        },
+                    ),
+                ),
+                (
+                    "b".to_string(),
+                    subgraph(
+                        Some("http://b"),
+                        SchemaSource::Subgraph {
+                            graphref: "graph@current".to_string(),
+                            subgraph: "b".to_string(),
+                        },
+                    ),
+                ),
+                (
+                    "c".to_string(),
+                    subgraph(
+                        Some("http://c"),
+                        SchemaSource::Sdl {
+                            sdl: "type Query { c: Int }".to_string(),
+                        },
+                    ),
+                ),
+            ]),
+            None,
+        );
+
+        let errors = config
+            .resolve(&FileSchemaResolver, Path::new("/nonexistent"))
+            .unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(
+            &errors[0],
+            ConfigError::SchemaNotResolved { subgraph_name, message }
+                if subgraph_name == "a" && message.contains("/nonexistent/missing.graphql")
+        ));
+        assert_eq!(
+            errors[1].to_string(),
+            "Could not resolve the schema of subgraph \"b\": fetching subgraph b of graph@current is not supported."
+        );
+        assert_eq!(
+            errors[1].code().as_deref(),
+            Some("SUBGRAPH_SCHEMA_NOT_RESOLVED")
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn it_keeps_command_errors() {
+        let config = SupergraphConfig::new(
+            BTreeMap::from([(
+                "a".to_string(),
+                subgraph(
+                    Some("http://a"),
+                    SchemaSource::Command {
+                        command: "false".to_string(),
+                        args: Vec::new(),
+                        working_dir: None,
+                        env: HashMap::new(),
+                        timeout_secs: None,
+                    },
+                ),
+            )]),
+            None,
+        );
+
+        let errors = config
+            .resolve(&FileSchemaResolver, Path::new("."))
+            .unwrap_err();
+        assert!(matches!(
+            &errors[0],
+            ConfigError::CommandFailed { subgraph_name, failure: CommandFailure::Exited(1), .. } if subgraph_name == "a"
+        ));
+    }
+}
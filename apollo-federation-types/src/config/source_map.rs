@@ -0,0 +1,145 @@
+use std::path::{Path, PathBuf};
+
+use crate::config::ResolveError;
+
+/// Where each file of a [`SchemaSource::Files`](crate::config::SchemaSource::Files) subgraph
+/// is in its concatenated SDL, for reporting locations in the original files.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+/// One file of a [`SourceMap`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SourceFile {
+    pub path: PathBuf,
+    /// The one-based line of the concatenated SDL this file starts on.
+    pub start_line: usize,
+    pub line_count: usize,
+}
+
+impl SourceFile {
+    /// Whether a one-based line of the concatenated SDL is in this file.
+    pub fn contains(&self, line: usize) -> bool {
+        (self.start_line..self.start_line + self.line_count).contains(&line)
+    }
+}
+
+impl SourceMap {
+    /// The files, in the order they were concatenated.
+    pub fn files(&self) -> &[SourceFile] {
+        &self.files
+    }
+
+    /// The file and one-based line in it of a one-based line of the concatenated SDL.
+    pub fn locate(&self, line: usize) -> Option<(&Path, usize)> {
+        self.file_at(line)
+            .map(|file| (file.path.as_path(), line - file.start_line + 1))
+    }
+
+    /// The file a one-based line of the concatenated SDL is in.
+    pub fn file_at(&self, line: usize) -> Option<&SourceFile> {
+        self.files.iter().find(|file| file.contains(line))
+    }
+
+    /// Concatenates the SDL of files, ending each with a line break.
+    pub fn concatenate(files: Vec<(PathBuf, String)>) -> (String, SourceMap) {
+        let mut sdl = String::new();
+        let mut source_map = SourceMap::default();
+        for (path, contents) in files {
+            let start_line = sdl.matches('\n').count() + 1;
+            sdl.push_str(&contents);
+            if !sdl.ends_with('\n') {
+                sdl.push('\n');
+            }
+            source_map.files.push(SourceFile {
+                path,
+                start_line,
+                line_count: sdl.matches('\n').count() + 1 - start_line,
+            });
+        }
+        (sdl, source_map)
+    }
+}
+
+/// Expands paths and glob patterns relative to `config_dir`, in order. The matches of each
+/// pattern are sorted, and files matched more than once are only included the first time.
+/// Characters in `config_dir` itself are never treated as a pattern.
+pub(crate) fn expand_patterns(
+    config_dir: &Path,
+    patterns: &[String],
+) -> Result<Vec<PathBuf>, ResolveError> {
+    let mut paths = Vec::new();
+    for pattern in patterns {
+        let path = config_dir.join(pattern);
+        if !pattern.contains(['*', '?', '[']) {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+            continue;
+        }
+        let escaped_dir = glob::Pattern::escape(&config_dir.to_string_lossy());
+        let mut matches = glob::glob(&Path::new(&escaped_dir).join(pattern).to_string_lossy())
+            .map_err(|err| format!("invalid glob pattern \"{pattern}\": {err}"))?
+            .collect::<Result<Vec<_>, _>>()?;
+        if matches.is_empty() {
+            return Err(format!("no files match \"{pattern}\"").into());
+        }
+        matches.sort();
+        for path in matches {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::{prelude::*, TempDir};
+
+    use super::*;
+
+    #[test]
+    fn it_maps_lines_to_files() {
+        let (sdl, source_map) = SourceMap::concatenate(vec![
+            ("a.graphql".into(), "type Query {\n  a: A\n}".to_string()),
+            ("b.graphql".into(), "type A {\n  b: Int\n}\n".to_string()),
+        ]);
+        assert_eq!(sdl, "type Query {\n  a: A\n}\ntype A {\n  b: Int\n}\n");
+        assert_eq!(source_map.locate(2), Some((Path::new("a.graphql"), 2)));
+        assert_eq!(source_map.locate(4), Some((Path::new("b.graphql"), 1)));
+        assert_eq!(source_map.locate(6), Some((Path::new("b.graphql"), 3)));
+        assert_eq!(source_map.locate(7), None);
+    }
+
+    #[test]
+    fn it_expands_globs_in_order() {
+        let config_dir = TempDir::new().unwrap();
+        for file in ["schema.graphql", "types/b.graphql", "types/a.graphql"] {
+            config_dir.child(file).touch().unwrap();
+        }
+        let patterns = ["schema.graphql", "types/*.graphql", "types/a.graphql"].map(String::from);
+
+        let paths = expand_patterns(config_dir.path(), &patterns).unwrap();
+        assert_eq!(
+            paths,
+            ["schema.graphql", "types/a.graphql", "types/b.graphql"]
+                .map(|file| config_dir.path().join(file))
+        );
+
+        let error = expand_patterns(config_dir.path(), &["missing/*".to_string()]).unwrap_err();
+        assert_eq!(error.to_string(), "no files match \"missing/*\"");
+    }
+
+    #[test]
+    fn it_escapes_the_config_dir() {
+        let parent = TempDir::new().unwrap();
+        let config_dir = parent.child("schemas [v2]");
+        config_dir.child("types/a.graphql").touch().unwrap();
+
+        let paths = expand_patterns(config_dir.path(), &["types/*.graphql".to_string()]).unwrap();
+        assert_eq!(paths, [config_dir.path().join("types/a.graphql")]);
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt::{self, Display};
 use std::path::PathBuf;
 use url::Url;
 
@@ -30,7 +31,7 @@ impl SubgraphConfig {
 }
 
 /// Options for getting SDL:
-/// the graph registry, a file, or an introspection URL.
+/// the graph registry, files, an introspection URL, or a command.
 ///
 /// NOTE: Introspection strips all comments and directives
 /// from the SDL.
@@ -44,6 +45,12 @@ pub enum SchemaSource {
     File {
         file: PathBuf,
     },
+    /// Paths or glob patterns, relative to the config file, of files whose SDL is concatenated
+    /// in order. The matches of each glob are sorted, and a file matched more than once is only
+    /// included the first time.
+    Files {
+        files: Vec<String>,
+    },
     SubgraphIntrospection {
         subgraph_url: Url,
         introspection_headers: Option<HashMap<String, String>>,
@@ -55,6 +62,47 @@ pub enum SchemaSource {
     Sdl {
         sdl: String,
     },
+    /// A command which prints the subgraph's SDL, for code-first subgraphs. It's run in
+    /// `working_dir`, relative to the config file, with `env` added to the environment, and is
+    /// killed after `timeout_secs` (60 by default).
+    Command {
+        command: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        args: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        working_dir: Option<PathBuf>,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        env: HashMap<String, String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_secs: Option<u64>,
+    },
+}
+
+/// A short description of where the SDL comes from, like `file ./films.graphql`.
+impl Display for SchemaSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaSource::File { file } => write!(f, "file {}", file.display()),
+            SchemaSource::Files { files } => write!(f, "files {}", files.join(", ")),
+            SchemaSource::SubgraphIntrospection { subgraph_url, .. } => {
+                write!(f, "introspection of {subgraph_url}")
+            }
+            SchemaSource::Subgraph { graphref, subgraph } => {
+                write!(f, "subgraph {subgraph} of {graphref}")
+            }
+            SchemaSource::Sdl { .. } => write!(f, "inline SDL"),
+            SchemaSource::Command { command, args, .. } => {
+                write!(
+                    f,
+                    "command {}",
+                    std::iter::once(command.as_str())
+                        .chain(args.iter().map(String::as_str))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                )
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -130,4 +178,33 @@ sdl: |
         };
         assert_eq!(source, expected);
     }
+
+    #[test]
+    fn test_command() {
+        let yaml = r#"
+command: npm
+args: [run, --silent, print-schema]
+working_dir: ./products
+env:
+  NODE_ENV: production
+timeout_secs: 120"#;
+        let source: SchemaSource = from_str(yaml).unwrap();
+        let expected = SchemaSource::Command {
+            command: "npm".to_string(),
+            args: vec![
+                "run".to_string(),
+                "--silent".to_string(),
+                "print-schema".to_string(),
+            ],
+            working_dir: Some("./products".into()),
+            env: [("NODE_ENV".to_string(), "production".to_string())].into(),
+            timeout_secs: Some(120),
+        };
+        assert_eq!(source, expected);
+
+        let source: SchemaSource = from_str("command: ./print-schema.sh").unwrap();
+        assert!(
+            matches!(source, SchemaSource::Command { args, working_dir: None, timeout_secs: None, .. } if args.is_empty())
+        );
+    }
 }
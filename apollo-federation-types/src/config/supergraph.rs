@@ -1,9 +1,20 @@
-use std::{collections::BTreeMap, fs, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    config::{ConfigError, ConfigResult, FederationVersion, SubgraphConfig},
+    config::{
+        command::{run_command, DEFAULT_COMMAND_TIMEOUT},
+        interpolation::interpolate,
+        source_map::expand_patterns,
+        ConfigError, ConfigFormat, ConfigResult, FederationVersion, FieldConflict, MergeReport,
+        MergeStrategy, ResolveError, SchemaResolver, SchemaSource, SourceMap, SubgraphConfig,
+    },
     javascript::SubgraphDefinition,
 };
 
@@ -17,6 +28,11 @@ pub struct SupergraphConfig {
 
     // The version requirement for the supergraph binary.
     federation_version: Option<FederationVersion>,
+
+    // Other config files this one overrides, relative to this one. They're
+    // merged when loading a file, so this is empty afterwards.
+    #[serde(default, alias = "include", skip_serializing_if = "Vec::is_empty")]
+    extends: Vec<PathBuf>,
 }
 
 impl SupergraphConfig {
@@ -28,14 +44,18 @@ impl SupergraphConfig {
         SupergraphConfig {
             subgraphs,
             federation_version,
+            extends: Vec::new(),
         }
     }
     /// Create a new SupergraphConfig from a YAML string in memory.
     pub fn new_from_yaml(yaml: &str) -> ConfigResult<SupergraphConfig> {
-        let parsed_config: SupergraphConfig =
-            serde_yaml::from_str(yaml).map_err(|e| ConfigError::InvalidConfiguration {
-                message: e.to_string(),
-            })?;
+        let parsed_config: SupergraphConfig = serde_path_to_error::deserialize(
+            serde_yaml::Deserializer::from_str(yaml),
+        )
+        .map_err(|error| {
+            let (error, key_path) = split_key_path(error);
+            yaml_parse_error(error, key_path)
+        })?;
 
         log::debug!("{parsed_config:?}");
 
@@ -45,8 +65,25 @@ impl SupergraphConfig {
     /// Create a new SupergraphConfig from a JSON string in memory.
     pub fn new_from_json(json: &str) -> ConfigResult<SupergraphConfig> {
         let parsed_config: SupergraphConfig =
-            serde_json::from_str(json).map_err(|e| ConfigError::InvalidConfiguration {
-                message: e.to_string(),
+            serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_str(json))
+                .map_err(|error| {
+                    let (error, key_path) = split_key_path(error);
+                    json_parse_error(error, key_path)
+                })?;
+
+        log::debug!("{parsed_config:?}");
+
+        Ok(parsed_config)
+    }
+
+    /// Create a new SupergraphConfig from a TOML string in memory.
+    pub fn new_from_toml(toml: &str) -> ConfigResult<SupergraphConfig> {
+        let deserializer = toml::de::Deserializer::parse(toml)
+            .map_err(|error| toml_parse_error(error, None, toml))?;
+        let parsed_config: SupergraphConfig = serde_path_to_error::deserialize(deserializer)
+            .map_err(|error| {
+                let (error, key_path) = split_key_path(error);
+                toml_parse_error(error, key_path, toml)
             })?;
 
         log::debug!("{parsed_config:?}");
@@ -54,20 +91,153 @@ impl SupergraphConfig {
         Ok(parsed_config)
     }
 
+    /// Create a new SupergraphConfig from a string in memory in the given format.
+    pub fn new_from_str(contents: &str, format: ConfigFormat) -> ConfigResult<SupergraphConfig> {
+        match format {
+            ConfigFormat::Yaml => SupergraphConfig::new_from_yaml(contents),
+            ConfigFormat::Json => SupergraphConfig::new_from_json(contents),
+            ConfigFormat::Toml => SupergraphConfig::new_from_toml(contents),
+        }
+    }
+
     /// Create a new SupergraphConfig from a YAML file.
+    ///
+    /// The file can list other config files it overrides in `extends` (or
+    /// `include`), relative to itself. Those are loaded recursively and merged
+    /// in order, each overriding the ones before it, and then this file is
+    /// merged over them all, like [`SupergraphConfig::merge_subgraphs`] with
+    /// the federation version of the last file which has one. Relative schema
+    /// paths in extended files are rewritten to stay relative to this one.
+    /// Extended files can be in any format [`SupergraphConfig::from_path`]
+    /// detects.
     pub fn new_from_yaml_file<P: Into<PathBuf>>(config_path: P) -> ConfigResult<SupergraphConfig> {
         let config_path: PathBuf = config_path.into();
-        let supergraph_yaml =
-            fs::read_to_string(&config_path).map_err(|e| ConfigError::MissingFile {
-                file_path: config_path.display().to_string(),
-                message: e.to_string(),
-            })?;
+        SupergraphConfig::load_file(&config_path, Some(ConfigFormat::Yaml), &mut Vec::new())
+    }
+
+    /// Create a new SupergraphConfig from a YAML, JSON or TOML file, detecting
+    /// the format by its extension, or by its contents if the extension isn't
+    /// one of `.yaml`, `.yml`, `.json` or `.toml`. Configs it extends are
+    /// loaded like [`SupergraphConfig::new_from_yaml_file`].
+    pub fn from_path<P: AsRef<Path>>(config_path: P) -> ConfigResult<SupergraphConfig> {
+        SupergraphConfig::load_file(config_path.as_ref(), None, &mut Vec::new())
+    }
 
-        let parsed_config = SupergraphConfig::new_from_yaml(&supergraph_yaml)?;
+    /// Loads a config file and what it extends, detecting its format unless
+    /// it's given. `extending` is the canonical paths of the files which are
+    /// extending this one, to detect cycles.
+    fn load_file(
+        config_path: &Path,
+        format: Option<ConfigFormat>,
+        extending: &mut Vec<PathBuf>,
+    ) -> ConfigResult<SupergraphConfig> {
+        let canonical_path =
+            fs::canonicalize(config_path).unwrap_or_else(|_| config_path.to_path_buf());
+        if let Some(index) = extending.iter().position(|path| *path == canonical_path) {
+            let file_paths = extending[index..]
+                .iter()
+                .chain([&canonical_path])
+                .map(|path| path.display().to_string())
+                .collect();
+            return Err(ConfigError::ExtendsCycle { file_paths });
+        }
+
+        let mut config = SupergraphConfig::read_file(config_path, format)?;
+        let extends = std::mem::take(&mut config.extends);
+        let config_dir = config_path.parent().unwrap_or(Path::new(""));
+        extending.push(canonical_path);
+        let mut merged: Option<SupergraphConfig> = None;
+        for base_path in extends {
+            let mut base =
+                SupergraphConfig::load_file(&config_dir.join(&base_path), None, extending)?;
+            base.rebase(base_path.parent().unwrap_or(Path::new("")));
+            merged = Some(match merged {
+                Some(mut merged) => {
+                    merged.merge_extended(base);
+                    merged
+                }
+                None => base,
+            });
+        }
+        extending.pop();
+
+        Ok(match merged {
+            Some(mut merged) => {
+                merged.merge_extended(config);
+                merged
+            }
+            None => config,
+        })
+    }
+
+    fn read_file(
+        config_path: &Path,
+        format: Option<ConfigFormat>,
+    ) -> ConfigResult<SupergraphConfig> {
+        let contents = fs::read_to_string(config_path).map_err(|e| ConfigError::MissingFile {
+            file_path: config_path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        let format = format
+            .or_else(|| ConfigFormat::from_extension(config_path))
+            .unwrap_or_else(|| ConfigFormat::detect(&contents));
+
+        let parsed_config =
+            SupergraphConfig::new_from_str(&contents, format).map_err(|error| match error {
+                ConfigError::ParseError {
+                    message,
+                    line,
+                    column,
+                    key_path,
+                    ..
+                } => ConfigError::ParseError {
+                    message,
+                    file_path: Some(config_path.display().to_string()),
+                    line,
+                    column,
+                    key_path,
+                },
+                error => error,
+            })?;
 
         Ok(parsed_config)
     }
 
+    /// Merges a config which extends this one over it.
+    fn merge_extended(&mut self, other: SupergraphConfig) {
+        self.merge_subgraphs(&other);
+        if other.federation_version.is_some() {
+            self.federation_version = other.federation_version;
+        }
+    }
+
+    /// Makes the relative paths of schema sources relative to the directory
+    /// `dir` is relative to, for a config file in `dir`.
+    fn rebase(&mut self, dir: &Path) {
+        if dir.as_os_str().is_empty() {
+            return;
+        }
+        for subgraph_config in self.subgraphs.values_mut() {
+            match &mut subgraph_config.schema {
+                SchemaSource::File { file } => *file = dir.join(&*file),
+                SchemaSource::Files { files } => {
+                    for file in files {
+                        *file = dir.join(&*file).to_string_lossy().into_owned();
+                    }
+                }
+                SchemaSource::Command { working_dir, .. } => {
+                    *working_dir = Some(match working_dir {
+                        Some(working_dir) => dir.join(working_dir),
+                        None => dir.to_path_buf(),
+                    });
+                }
+                SchemaSource::SubgraphIntrospection { .. }
+                | SchemaSource::Subgraph { .. }
+                | SchemaSource::Sdl { .. } => {}
+            }
+        }
+    }
+
     /// Returns a Vec of resolved subgraphs, if and only if they are all resolved.
     /// Resolved in this sense means that each subgraph config includes
     /// a name, a URL, and raw SDL.
@@ -100,6 +270,233 @@ impl SupergraphConfig {
         }
     }
 
+    /// Returns a copy of this configuration where every subgraph's schema is
+    /// [`SchemaSource::Sdl`], fetching the others with `resolver`. Relative
+    /// schema files are read from `config_dir`, which should be the directory
+    /// of the config file.
+    ///
+    /// Introspected subgraphs without a routing URL are routed to their
+    /// introspection URL. If any schema can't be fetched, there's one error
+    /// for each of those subgraphs, which is a
+    /// [`ConfigError::SchemaNotResolved`] unless the resolver returned a
+    /// [`ConfigError`] or a [`SchemaSource::Command`] failed.
+    pub fn resolve(
+        &self,
+        resolver: &impl SchemaResolver,
+        config_dir: &Path,
+    ) -> Result<SupergraphConfig, Vec<ConfigError>> {
+        self.resolve_with_source_maps(resolver, config_dir)
+            .map(|(resolved, _)| resolved)
+    }
+
+    /// Like [`SupergraphConfig::resolve`], but also returns where each file
+    /// of [`SchemaSource::Files`] subgraphs ended up in their concatenated
+    /// SDL, keyed by subgraph name.
+    pub fn resolve_with_source_maps(
+        &self,
+        resolver: &impl SchemaResolver,
+        config_dir: &Path,
+    ) -> Result<(SupergraphConfig, BTreeMap<String, SourceMap>), Vec<ConfigError>> {
+        let mut subgraphs = BTreeMap::new();
+        let mut source_maps = BTreeMap::new();
+        let mut errors = Vec::new();
+        for (subgraph_name, subgraph_config) in &self.subgraphs {
+            let mut routing_url = subgraph_config.routing_url.clone();
+            let sdl = match &subgraph_config.schema {
+                SchemaSource::File { file } => resolver.read_file(&config_dir.join(file)),
+                SchemaSource::Files { files } => {
+                    read_files(resolver, config_dir, files).map(|(sdl, source_map)| {
+                        source_maps.insert(subgraph_name.clone(), source_map);
+                        sdl
+                    })
+                }
+                SchemaSource::SubgraphIntrospection {
+                    subgraph_url,
+                    introspection_headers,
+                } => {
+                    routing_url.get_or_insert_with(|| subgraph_url.to_string());
+                    resolver.introspect(
+                        subgraph_url,
+                        introspection_headers
+                            .as_ref()
+                            .unwrap_or(&Default::default()),
+                    )
+                }
+                SchemaSource::Subgraph { graphref, subgraph } => {
+                    resolver.fetch_subgraph(graphref, subgraph)
+                }
+                SchemaSource::Sdl { sdl } => Ok(sdl.clone()),
+                SchemaSource::Command {
+                    command,
+                    args,
+                    working_dir,
+                    env,
+                    timeout_secs,
+                } => run_command(
+                    subgraph_name,
+                    command,
+                    args,
+                    working_dir.as_deref(),
+                    env,
+                    timeout_secs.map_or(DEFAULT_COMMAND_TIMEOUT, Duration::from_secs),
+                    config_dir,
+                )
+                .map_err(ResolveError::from),
+            };
+            match sdl {
+                Ok(sdl) => {
+                    subgraphs.insert(
+                        subgraph_name.clone(),
+                        SubgraphConfig {
+                            routing_url,
+                            schema: SchemaSource::Sdl { sdl },
+                        },
+                    );
+                }
+                Err(err) => errors.push(match err.downcast::<ConfigError>() {
+                    Ok(err) => *err,
+                    Err(err) => ConfigError::SchemaNotResolved {
+                        subgraph_name: subgraph_name.clone(),
+                        message: err.to_string(),
+                    },
+                }),
+            }
+        }
+        if errors.is_empty() {
+            let resolved = SupergraphConfig {
+                subgraphs,
+                federation_version: self.federation_version.clone(),
+                extends: self.extends.clone(),
+            };
+            Ok((resolved, source_maps))
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Returns the schema file of each subgraph whose schema comes from a single file, as written
+    /// in the configuration.
+    pub fn subgraph_files(&self) -> BTreeMap<String, PathBuf> {
+        self.subgraphs
+            .iter()
+            .filter_map(
+                |(subgraph_name, subgraph_config)| match &subgraph_config.schema {
+                    SchemaSource::File { file } => Some((subgraph_name.clone(), file.clone())),
+                    _ => None,
+                },
+            )
+            .collect()
+    }
+
+    /// Expands `${env.NAME}` and `${env.NAME:-default}` in every subgraph's routing URL, schema
+    /// file paths, introspection URL and headers, and schema command, using the process
+    /// environment.
+    ///
+    /// The default is used when the variable is unset or empty. A variable which is unset and has
+    /// no default is a [`ConfigError::MissingEnvironmentVariable`]. Variables can't be used in
+    /// the host of an introspection URL, since hosts are lowercased when the config is parsed, so
+    /// they're a [`ConfigError::InvalidConfiguration`]. The config is only changed if every
+    /// variable could be expanded.
+    pub fn interpolate_env(&mut self) -> ConfigResult<()> {
+        self.interpolate_with(|name| std::env::var(name).ok())
+    }
+
+    /// Like [`SupergraphConfig::interpolate_env`], but looks up variables with `lookup` instead
+    /// of in the process environment.
+    pub fn interpolate_with(
+        &mut self,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> ConfigResult<()> {
+        let mut subgraphs = self.subgraphs.clone();
+        for (subgraph_name, subgraph_config) in &mut subgraphs {
+            let key_path = format!("subgraphs.{subgraph_name}");
+            if let Some(routing_url) = &mut subgraph_config.routing_url {
+                *routing_url =
+                    interpolate(routing_url, &format!("{key_path}.routing_url"), &lookup)?;
+            }
+            match &mut subgraph_config.schema {
+                SchemaSource::File { file } => {
+                    if let Some(path) = file.to_str() {
+                        *file =
+                            interpolate(path, &format!("{key_path}.schema.file"), &lookup)?.into();
+                    }
+                }
+                SchemaSource::Files { files } => {
+                    for (index, file) in files.iter_mut().enumerate() {
+                        *file = interpolate(
+                            file,
+                            &format!("{key_path}.schema.files.{index}"),
+                            &lookup,
+                        )?;
+                    }
+                }
+                SchemaSource::SubgraphIntrospection {
+                    subgraph_url,
+                    introspection_headers,
+                } => {
+                    let url_key_path = format!("{key_path}.schema.subgraph_url");
+                    if subgraph_url
+                        .host_str()
+                        .is_some_and(|host| host.contains("${"))
+                    {
+                        return Err(ConfigError::InvalidConfiguration {
+                            message: format!(
+                                "{url_key_path} can't use environment variables in its host, which is lowercased when the URL is parsed"
+                            ),
+                        });
+                    }
+                    // Braces in the path of a URL are percent-encoded when it's parsed
+                    let url = subgraph_url
+                        .as_str()
+                        .replace("%7B", "{")
+                        .replace("%7D", "}");
+                    let interpolated = interpolate(&url, &url_key_path, &lookup)?;
+                    if interpolated != url {
+                        *subgraph_url = interpolated.parse().map_err(|err| {
+                            ConfigError::InvalidConfiguration {
+                                message: format!("{url_key_path} is not a valid URL: {err}"),
+                            }
+                        })?;
+                    }
+                    for (header, value) in introspection_headers.iter_mut().flatten() {
+                        *value = interpolate(
+                            value,
+                            &format!("{key_path}.schema.introspection_headers.{header}"),
+                            &lookup,
+                        )?;
+                    }
+                }
+                SchemaSource::Command {
+                    command,
+                    args,
+                    working_dir,
+                    env,
+                    ..
+                } => {
+                    *command =
+                        interpolate(command, &format!("{key_path}.schema.command"), &lookup)?;
+                    for (index, arg) in args.iter_mut().enumerate() {
+                        *arg =
+                            interpolate(arg, &format!("{key_path}.schema.args.{index}"), &lookup)?;
+                    }
+                    if let Some(path) = working_dir.as_ref().and_then(|dir| dir.to_str()) {
+                        *working_dir = Some(
+                            interpolate(path, &format!("{key_path}.schema.working_dir"), &lookup)?
+                                .into(),
+                        );
+                    }
+                    for (name, value) in env.iter_mut() {
+                        *value =
+                            interpolate(value, &format!("{key_path}.schema.env.{name}"), &lookup)?;
+                    }
+                }
+                SchemaSource::Subgraph { .. } | SchemaSource::Sdl { .. } => {}
+            }
+        }
+        self.subgraphs = subgraphs;
+        Ok(())
+    }
+
     /// Updates the federation_version for a configuration
     pub fn set_federation_version(&mut self, federation_version: FederationVersion) {
         self.federation_version = Some(federation_version);
@@ -110,8 +507,110 @@ impl SupergraphConfig {
         self.federation_version.clone()
     }
 
+    pub(crate) fn subgraphs_mut(&mut self) -> &mut BTreeMap<String, SubgraphConfig> {
+        &mut self.subgraphs
+    }
+
+    pub(crate) fn federation_version_mut(&mut self) -> &mut Option<FederationVersion> {
+        &mut self.federation_version
+    }
+
+    /// Merges another [`SupergraphConfig`] into this one, including its
+    /// federation version, returning every field the configs disagreed on.
+    ///
+    /// Subgraphs only in `other` are added. For fields both configs set,
+    /// `strategy` decides which value is kept; a routing URL or federation
+    /// version `other` leaves unset is never a conflict. With
+    /// [`MergeStrategy::Reject`], this config is unchanged if there are any
+    /// conflicts.
+    pub fn merge(
+        &mut self,
+        other: &SupergraphConfig,
+        strategy: MergeStrategy,
+    ) -> ConfigResult<MergeReport> {
+        let mut report = MergeReport::default();
+        let mut conflicts = Vec::new();
+        let mut conflict = |key_path: String, existing: String, other: String| {
+            let field = FieldConflict {
+                key_path,
+                existing,
+                other,
+            };
+            conflicts.push(field.key_path.clone());
+            match strategy {
+                MergeStrategy::Override | MergeStrategy::Reject => report.overridden.push(field),
+                MergeStrategy::KeepExisting => report.kept.push(field),
+            }
+        };
+
+        let mut merged = self.clone();
+        if let (Some(existing), Some(other_version)) =
+            (&self.federation_version, &other.federation_version)
+        {
+            if existing != other_version {
+                conflict(
+                    "federation_version".to_string(),
+                    existing.to_config_string(),
+                    other_version.to_config_string(),
+                );
+            }
+        }
+        if other.federation_version.is_some()
+            && (strategy != MergeStrategy::KeepExisting || self.federation_version.is_none())
+        {
+            merged.federation_version = other.federation_version.clone();
+        }
+
+        for (subgraph_name, other_subgraph) in &other.subgraphs {
+            let Some(subgraph) = merged.subgraphs.get_mut(subgraph_name) else {
+                report.added_subgraphs.push(subgraph_name.clone());
+                merged
+                    .subgraphs
+                    .insert(subgraph_name.clone(), other_subgraph.clone());
+                continue;
+            };
+            let key_path = format!("subgraphs.{subgraph_name}");
+            if let Some(other_url) = &other_subgraph.routing_url {
+                match &subgraph.routing_url {
+                    Some(url) if url != other_url => {
+                        conflict(
+                            format!("{key_path}.routing_url"),
+                            url.clone(),
+                            other_url.clone(),
+                        );
+                        if strategy != MergeStrategy::KeepExisting {
+                            subgraph.routing_url = Some(other_url.clone());
+                        }
+                    }
+                    Some(_) => {}
+                    None => subgraph.routing_url = Some(other_url.clone()),
+                }
+            }
+            if subgraph.schema != other_subgraph.schema {
+                conflict(
+                    format!("{key_path}.schema"),
+                    subgraph.schema.to_string(),
+                    other_subgraph.schema.to_string(),
+                );
+                if strategy != MergeStrategy::KeepExisting {
+                    subgraph.schema = other_subgraph.schema.clone();
+                }
+            }
+        }
+
+        if strategy == MergeStrategy::Reject && !conflicts.is_empty() {
+            return Err(ConfigError::MergeConflict {
+                key_paths: conflicts,
+            });
+        }
+        *self = merged;
+        Ok(report)
+    }
+
     /// Merges the subgraphs of another [`SupergraphConfig`] into this one; the
-    /// other config takes precedence when there are overlaps
+    /// other config takes precedence when there are overlaps. See
+    /// [`SupergraphConfig::merge`] to also merge the federation version and
+    /// see what changed.
     pub fn merge_subgraphs(&mut self, other: &SupergraphConfig) {
         for (key, other_subgraph) in other.subgraphs.iter() {
             let other_subgraph = other_subgraph.clone();
@@ -132,6 +631,83 @@ impl SupergraphConfig {
     }
 }
 
+fn read_files(
+    resolver: &impl SchemaResolver,
+    config_dir: &Path,
+    patterns: &[String],
+) -> Result<(String, SourceMap), ResolveError> {
+    let files = expand_patterns(config_dir, patterns)?
+        .into_iter()
+        .map(|path| Ok((path.clone(), resolver.read_file(&path)?)))
+        .collect::<Result<_, ResolveError>>()?;
+    Ok(SourceMap::concatenate(files))
+}
+
+/// Splits off the path of the key which failed to deserialize, like `subgraphs.films.routing_url`,
+/// which is `None` if the document itself couldn't be parsed.
+fn split_key_path<E>(error: serde_path_to_error::Error<E>) -> (E, Option<String>) {
+    let path = error.path().to_string();
+    (error.into_inner(), (path != ".").then_some(path))
+}
+
+/// Splits the location off a YAML error's message, along with the key path which `serde_yaml`
+/// also prefixes it with.
+fn yaml_parse_error(error: serde_yaml::Error, key_path: Option<String>) -> ConfigError {
+    let message = error.to_string();
+    let Some(location) = error.location() else {
+        return ConfigError::InvalidConfiguration { message };
+    };
+    let suffix = format!(" at line {} column {}", location.line(), location.column());
+    let message = message.strip_suffix(&suffix).unwrap_or(&message);
+    let message = key_path
+        .as_ref()
+        .and_then(|key_path| message.strip_prefix(&format!("{key_path}: ")))
+        .unwrap_or(message);
+    ConfigError::ParseError {
+        message: message.to_string(),
+        file_path: None,
+        line: location.line(),
+        column: location.column(),
+        key_path,
+    }
+}
+
+fn json_parse_error(error: serde_json::Error, key_path: Option<String>) -> ConfigError {
+    if error.line() == 0 {
+        return ConfigError::InvalidConfiguration {
+            message: error.to_string(),
+        };
+    }
+    let suffix = format!(" at line {} column {}", error.line(), error.column());
+    let message = error.to_string();
+    ConfigError::ParseError {
+        message: message
+            .strip_suffix(&suffix)
+            .unwrap_or(&message)
+            .to_string(),
+        file_path: None,
+        line: error.line(),
+        column: error.column(),
+        key_path,
+    }
+}
+
+/// Converts the byte range of a TOML error to a line and column.
+fn toml_parse_error(error: toml::de::Error, key_path: Option<String>, toml: &str) -> ConfigError {
+    let message = error.message().trim_end().to_string();
+    let Some(before) = error.span().and_then(|span| toml.get(..span.start)) else {
+        return ConfigError::InvalidConfiguration { message };
+    };
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    ConfigError::ParseError {
+        message,
+        file_path: None,
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+        key_path,
+    }
+}
+
 impl From<Vec<SubgraphDefinition>> for SupergraphConfig {
     fn from(input: Vec<SubgraphDefinition>) -> Self {
         let mut subgraphs = BTreeMap::new();
@@ -149,6 +725,7 @@ impl From<Vec<SubgraphDefinition>> for SupergraphConfig {
         Self {
             subgraphs,
             federation_version: None,
+            extends: Vec::new(),
         }
     }
 }
@@ -169,19 +746,23 @@ impl FromIterator<(String, SubgraphConfig)> for SupergraphConfig {
         Self {
             subgraphs: iter.into_iter().collect::<BTreeMap<_, _>>(),
             federation_version: None,
+            extends: Vec::new(),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::BTreeMap, convert::TryFrom, fs, path::PathBuf};
+    use std::{collections::BTreeMap, fs};
 
     use assert_fs::TempDir;
     use semver::Version;
 
     use super::SupergraphConfig;
-    use crate::config::{FederationVersion, SchemaSource, SubgraphConfig};
+    use crate::config::{
+        ConfigError, ConfigFormat, FederationVersion, FieldConflict, MergeStrategy, SchemaSource,
+        SubgraphConfig,
+    };
 
     #[test]
     fn it_can_parse_valid_config_without_version() {
@@ -509,7 +1090,7 @@ subgraphs:
 "#;
 
         let tmp_home = TempDir::new().unwrap();
-        let mut config_path = PathBuf::try_from(tmp_home.path().to_path_buf()).unwrap();
+        let mut config_path = tmp_home.path().to_path_buf();
         config_path.push("config.yaml");
         fs::write(&config_path, raw_good_yaml).unwrap();
 
@@ -744,4 +1325,462 @@ subgraphs:
         assert!(s.get_subgraph_definitions().is_ok());
         assert_eq!(1, s.get_subgraph_definitions().unwrap().len());
     }
+
+    #[test]
+    fn test_subgraph_files() {
+        let raw_good_yaml = r#"---
+subgraphs:
+  films:
+    routing_url: https://films.example.com
+    schema:
+      file: ./good-films.graphql
+  people:
+    routing_url: https://people.example.com
+    schema:
+      subgraph_url: https://people.example.com
+"#;
+
+        let config = SupergraphConfig::new_from_yaml(raw_good_yaml).unwrap();
+        assert_eq!(
+            config.subgraph_files(),
+            BTreeMap::from([("films".to_string(), "./good-films.graphql".into())])
+        );
+    }
+
+    #[test]
+    fn it_reports_where_parsing_failed() {
+        let raw_bad_yaml = r#"---
+subgraphs:
+  films:
+    routing_url: [https://films.example.com]
+    schema:
+      file: ./good-films.graphql
+"#;
+        let tmp_home = TempDir::new().unwrap();
+        let config_path = tmp_home.path().join("supergraph.yaml");
+        fs::write(&config_path, raw_bad_yaml).unwrap();
+
+        let error = SupergraphConfig::new_from_yaml_file(&config_path).unwrap_err();
+        let ConfigError::ParseError {
+            message,
+            file_path,
+            line,
+            column,
+            key_path,
+        } = &error
+        else {
+            panic!("expected a parse error, got {error:?}");
+        };
+        assert_eq!(message, "invalid type: sequence, expected a string");
+        assert_eq!(file_path, &Some(config_path.display().to_string()));
+        assert_eq!((*line, *column), (4, 18));
+        assert_eq!(key_path.as_deref(), Some("subgraphs.films.routing_url"));
+        assert_eq!(error.code().as_deref(), Some("CONFIG_INVALID"));
+    }
+
+    #[test]
+    fn it_reports_where_parsing_failed_without_a_key_path() {
+        let error = SupergraphConfig::new_from_yaml("subgraphs: [").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Could not parse supergraph config at <input>:2:1: while parsing a node, did not find expected node content."
+        );
+
+        let error = SupergraphConfig::new_from_toml("subgraphs = [").unwrap_err();
+        assert!(matches!(
+            error,
+            ConfigError::ParseError {
+                line: 1,
+                key_path: None,
+                ..
+            }
+        ));
+    }
+
+    #[rstest::rstest]
+    #[case(ConfigFormat::Yaml, "subgraphs:\n  films:\n    routing_url: [a]\n", 3)]
+    #[case(
+        ConfigFormat::Json,
+        "{\n  \"subgraphs\": {\n    \"films\": {\n      \"routing_url\": [\"a\"]\n    }\n  }\n}",
+        4
+    )]
+    #[case(ConfigFormat::Toml, "[subgraphs.films]\nrouting_url = [\"a\"]\n", 2)]
+    fn it_reports_key_paths_in_every_format(
+        #[case] format: ConfigFormat,
+        #[case] contents: &str,
+        #[case] expected_line: usize,
+    ) {
+        let error = SupergraphConfig::new_from_str(contents, format).unwrap_err();
+        let ConfigError::ParseError {
+            message,
+            line,
+            key_path,
+            ..
+        } = &error
+        else {
+            panic!("expected a parse error, got {error:?}");
+        };
+        assert!(message.starts_with("invalid type: sequence"), "{message}");
+        assert_eq!(*line, expected_line);
+        assert_eq!(key_path.as_deref(), Some("subgraphs.films.routing_url"));
+    }
+
+    #[test]
+    fn test_interpolate_with() {
+        let raw_yaml = r#"---
+subgraphs:
+  films:
+    routing_url: https://${env.FILMS_HOST:-films.example.com}/graphql
+    schema:
+      file: ./${env.SCHEMA_DIR}/films.graphql
+  people:
+    routing_url: https://people.example.com
+    schema:
+      subgraph_url: https://people.example.com/${env.PEOPLE_PATH}
+      introspection_headers:
+        Authorization: Bearer ${env.TOKEN}
+"#;
+        let lookup = |name: &str| match name {
+            "SCHEMA_DIR" => Some("schemas".to_string()),
+            "PEOPLE_PATH" => Some("graphql".to_string()),
+            "TOKEN" => Some("secret".to_string()),
+            _ => None,
+        };
+        let mut config = SupergraphConfig::new_from_yaml(raw_yaml).unwrap();
+        config.interpolate_with(lookup).unwrap();
+
+        let films = &config.subgraphs["films"];
+        assert_eq!(
+            films.routing_url.as_deref(),
+            Some("https://films.example.com/graphql")
+        );
+        assert_eq!(
+            films.schema,
+            SchemaSource::File {
+                file: "./schemas/films.graphql".into()
+            }
+        );
+        assert_eq!(
+            config.subgraphs["people"].schema,
+            SchemaSource::SubgraphIntrospection {
+                subgraph_url: "https://people.example.com/graphql".parse().unwrap(),
+                introspection_headers: Some(
+                    [("Authorization".to_string(), "Bearer secret".to_string())].into()
+                ),
+            }
+        );
+
+        let mut config = SupergraphConfig::new_from_yaml(raw_yaml).unwrap();
+        let error = config.interpolate_with(|_| None).unwrap_err();
+        assert!(matches!(
+            error,
+            ConfigError::MissingEnvironmentVariable { name, key_path }
+                if name == "SCHEMA_DIR" && key_path == "subgraphs.films.schema.file"
+        ));
+        // Nothing is expanded unless everything can be
+        assert_eq!(
+            config.subgraphs["films"].routing_url.as_deref(),
+            Some("https://${env.FILMS_HOST:-films.example.com}/graphql")
+        );
+    }
+
+    #[test]
+    fn it_rejects_variables_in_introspection_url_hosts() {
+        let raw_yaml = r#"---
+subgraphs:
+  films:
+    schema:
+      subgraph_url: https://${env.FILMS_HOST}/graphql
+"#;
+        let mut config = SupergraphConfig::new_from_yaml(raw_yaml).unwrap();
+        let error = config
+            .interpolate_with(|name| {
+                (name == "FILMS_HOST").then(|| "films.example.com".to_string())
+            })
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Could not parse supergraph config: subgraphs.films.schema.subgraph_url can't use environment variables in its host, which is lowercased when the URL is parsed."
+        );
+    }
+
+    #[test]
+    fn it_loads_extended_configs() {
+        let tmp_home = TempDir::new().unwrap();
+        fs::create_dir_all(tmp_home.path().join("shared")).unwrap();
+        fs::create_dir_all(tmp_home.path().join("team")).unwrap();
+        fs::write(
+            tmp_home.path().join("shared/base.yaml"),
+            r#"---
+federation_version: =2.3.0
+subgraphs:
+  films:
+    routing_url: https://films.example.com
+    schema:
+      file: ./films.graphql
+  people:
+    routing_url: https://people.example.com
+    schema:
+      file: ./people.graphql
+"#,
+        )
+        .unwrap();
+        fs::write(
+            tmp_home.path().join("team/supergraph.yaml"),
+            r#"---
+extends:
+  - ../shared/base.yaml
+subgraphs:
+  people:
+    schema:
+      file: ./people.graphql
+  reviews:
+    routing_url: http://localhost:4002
+    schema:
+      file: ./reviews.graphql
+"#,
+        )
+        .unwrap();
+
+        let config =
+            SupergraphConfig::new_from_yaml_file(tmp_home.path().join("team/supergraph.yaml"))
+                .unwrap();
+        assert_eq!(
+            config.federation_version,
+            Some(FederationVersion::ExactFedTwo(Version::new(2, 3, 0)))
+        );
+        assert!(config.extends.is_empty());
+        assert_eq!(
+            config.subgraph_files(),
+            BTreeMap::from([
+                ("films".to_string(), "../shared/./films.graphql".into()),
+                ("people".to_string(), "./people.graphql".into()),
+                ("reviews".to_string(), "./reviews.graphql".into()),
+            ])
+        );
+        assert_eq!(
+            config.subgraphs["people"].routing_url.as_deref(),
+            Some("https://people.example.com")
+        );
+    }
+
+    #[test]
+    fn it_errs_on_extends_cycles() {
+        let tmp_home = TempDir::new().unwrap();
+        fs::write(
+            tmp_home.path().join("a.yaml"),
+            "include: [b.yaml]\nsubgraphs: {}\n",
+        )
+        .unwrap();
+        fs::write(
+            tmp_home.path().join("b.yaml"),
+            "include: [a.yaml]\nsubgraphs: {}\n",
+        )
+        .unwrap();
+
+        let error =
+            SupergraphConfig::new_from_yaml_file(tmp_home.path().join("a.yaml")).unwrap_err();
+        let ConfigError::ExtendsCycle { file_paths } = &error else {
+            panic!("expected a cycle, got {error}");
+        };
+        let file_names: Vec<&str> = file_paths
+            .iter()
+            .map(|path| path.rsplit(['/', '\\']).next().unwrap())
+            .collect();
+        assert_eq!(file_names, ["a.yaml", "b.yaml", "a.yaml"]);
+        assert_eq!(error.code().as_deref(), Some("CONFIG_EXTENDS_CYCLE"));
+    }
+
+    #[test]
+    fn test_merge() {
+        let base_config = SupergraphConfig::new_from_yaml(
+            r#"---
+federation_version: 2
+subgraphs:
+  films:
+    routing_url: https://films.example.com
+    schema:
+      file: ./films.graphql
+  robots:
+    schema:
+      file: ./robots.graphql
+"#,
+        )
+        .unwrap();
+        let override_config = SupergraphConfig::new_from_yaml(
+            r#"---
+federation_version: 1
+subgraphs:
+  films:
+    routing_url: http://localhost:4001
+    schema:
+      file: ./films.graphql
+  robots:
+    routing_url: https://robots.example.com
+    schema:
+      subgraph_url: http://localhost:4002
+  books:
+    routing_url: https://books.example.com
+    schema:
+      file: ./books.graphql
+"#,
+        )
+        .unwrap();
+
+        let mut merged = base_config.clone();
+        let report = merged
+            .merge(&override_config, MergeStrategy::Override)
+            .unwrap();
+        assert_eq!(report.added_subgraphs, ["books"]);
+        let overridden: Vec<String> = report.overridden.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            overridden,
+            [
+                "federation_version: 2 -> 1",
+                "subgraphs.films.routing_url: https://films.example.com -> http://localhost:4001",
+                "subgraphs.robots.schema: file ./robots.graphql -> introspection of http://localhost:4002/",
+            ]
+        );
+        assert!(report.kept.is_empty());
+        assert_eq!(
+            merged.federation_version,
+            Some(FederationVersion::LatestFedOne)
+        );
+        assert_eq!(
+            merged.subgraphs["robots"].routing_url.as_deref(),
+            Some("https://robots.example.com")
+        );
+
+        let mut merged = base_config.clone();
+        let report = merged
+            .merge(&override_config, MergeStrategy::KeepExisting)
+            .unwrap();
+        assert_eq!(report.kept.len(), 3);
+        assert_eq!(
+            merged.federation_version,
+            Some(FederationVersion::LatestFedTwo)
+        );
+        assert_eq!(
+            merged.subgraphs["robots"].schema,
+            base_config.subgraphs["robots"].schema
+        );
+        assert_eq!(
+            merged.subgraphs["robots"].routing_url.as_deref(),
+            Some("https://robots.example.com")
+        );
+        assert!(merged.subgraphs.contains_key("books"));
+
+        let mut merged = base_config.clone();
+        let error = merged
+            .merge(&override_config, MergeStrategy::Reject)
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            ConfigError::MergeConflict { key_paths } if key_paths.len() == 3
+        ));
+        assert_eq!(merged, base_config);
+    }
+
+    #[test]
+    fn it_reports_federation_versions_as_written_in_configs() {
+        let mut config =
+            SupergraphConfig::new_from_yaml("federation_version: =2.4.5\nsubgraphs: {}\n").unwrap();
+        let other =
+            SupergraphConfig::new_from_yaml("federation_version: 1\nsubgraphs: {}\n").unwrap();
+
+        let report = config.merge(&other, MergeStrategy::KeepExisting).unwrap();
+        assert_eq!(
+            report.kept,
+            [FieldConflict {
+                key_path: "federation_version".to_string(),
+                existing: "=2.4.5".to_string(),
+                other: "1".to_string(),
+            }]
+        );
+        assert_eq!(
+            report.kept[0].to_string(),
+            "federation_version: =2.4.5 -> 1"
+        );
+    }
+
+    #[test]
+    fn it_can_parse_valid_config_toml() {
+        let raw_good_toml = r#"
+federation_version = "=2.3.0"
+
+[subgraphs.films]
+routing_url = "https://films.example.com"
+schema = { file = "./good-films.graphql" }
+
+[subgraphs.people.schema]
+subgraph_url = "https://people.example.com"
+introspection_headers = { Authorization = "Bearer ${env.TOKEN}" }
+"#;
+
+        let config = SupergraphConfig::new_from_toml(raw_good_toml).unwrap();
+        assert_eq!(
+            config.federation_version,
+            Some(FederationVersion::ExactFedTwo(Version::new(2, 3, 0)))
+        );
+        assert_eq!(
+            config.subgraphs["films"].schema,
+            SchemaSource::File {
+                file: "./good-films.graphql".into()
+            }
+        );
+        assert!(matches!(
+            config.subgraphs["people"].schema,
+            SchemaSource::SubgraphIntrospection { .. }
+        ));
+
+        let error = SupergraphConfig::new_from_toml("[subgraphs.films]\nschema = 1\n").unwrap_err();
+        assert!(matches!(
+            error,
+            ConfigError::ParseError {
+                line: 2,
+                column: 10,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn it_detects_the_format_of_files() {
+        let tmp_home = TempDir::new().unwrap();
+        let expected = SupergraphConfig::new_from_yaml(
+            "subgraphs:\n  films:\n    routing_url: https://films.example.com\n    schema:\n      file: ./films.graphql\n",
+        )
+        .unwrap();
+        let files = [
+            (
+                "supergraph.toml",
+                "[subgraphs.films]\nrouting_url = \"https://films.example.com\"\nschema.file = \"./films.graphql\"\n",
+            ),
+            (
+                "supergraph.config",
+                r#"{ "subgraphs": { "films": { "routing_url": "https://films.example.com", "schema": { "file": "./films.graphql" } } } }"#,
+            ),
+            (
+                "supergraph",
+                "# no extension\n[subgraphs.films]\nrouting_url = \"https://films.example.com\"\nschema.file = \"./films.graphql\"\n",
+            ),
+        ];
+        for (file_name, contents) in files {
+            let config_path = tmp_home.path().join(file_name);
+            fs::write(&config_path, contents).unwrap();
+            assert_eq!(
+                SupergraphConfig::from_path(&config_path).unwrap(),
+                expected,
+                "{file_name}"
+            );
+        }
+
+        let config_path = tmp_home.path().join("broken.toml");
+        fs::write(&config_path, "subgraphs = [\n").unwrap();
+        assert!(matches!(
+            SupergraphConfig::from_path(&config_path),
+            Err(ConfigError::ParseError { file_path: Some(file_path), .. })
+                if file_path.ends_with("broken.toml")
+        ));
+    }
 }
@@ -100,6 +100,15 @@ impl FederationVersion {
         }
     }
 
+    /// The version as it's written in a supergraph config, like `1`, `2`, or `=2.4.5`, unlike
+    /// [`Display`], which writes the latest federation 1 version as `0`.
+    pub(crate) fn to_config_string(&self) -> String {
+        match self {
+            Self::LatestFedOne => "1".to_string(),
+            _ => self.to_string(),
+        }
+    }
+
     fn is_latest(&self) -> bool {
         matches!(self, Self::LatestFedOne) || matches!(self, Self::LatestFedTwo)
     }
@@ -340,6 +349,16 @@ mod test_federation_version {
     fn test_supports_arm_macos(#[case] version: FederationVersion, #[case] expected: bool) {
         assert_eq!(version.supports_arm_macos(), expected)
     }
+
+    #[rstest]
+    #[case::fed1_latest(FederationVersion::LatestFedOne, "1")]
+    #[case::fed1_exact(FederationVersion::ExactFedOne("0.37.2".parse().unwrap()), "=0.37.2")]
+    #[case::fed2_latest(FederationVersion::LatestFedTwo, "2")]
+    #[case::fed2_exact(FederationVersion::ExactFedTwo("2.4.5".parse().unwrap()), "=2.4.5")]
+    fn test_to_config_string(#[case] version: FederationVersion, #[case] expected: &str) {
+        assert_eq!(version.to_config_string(), expected);
+        assert_eq!(expected.parse::<FederationVersion>().unwrap(), version);
+    }
 }
 
 #[cfg(feature = "json_schema")]
@@ -21,7 +21,7 @@ pub struct BuildError {
 
     /// Other untyped JSON included in the build output.
     #[serde(flatten)]
-    other: crate::UncaughtJson,
+    pub(crate) other: crate::UncaughtJson,
 
     pub nodes: Option<Vec<BuildMessageLocation>>,
 
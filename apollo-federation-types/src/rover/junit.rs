@@ -0,0 +1,282 @@
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use crate::build_plugin::{BuildMessageLevel, BuildMessageLocation, PluginResult};
+use crate::rover::{BuildError, BuildHint, BuildResult};
+
+const SUITE_NAME: &str = "composition";
+const SUPERGRAPH_TEST_CASE: &str = "supergraph";
+
+/// Reports a composition run as JUnit XML, for CI systems which only aggregate test results.
+///
+/// There is one test case per subgraph in `subgraph_names`, which fails with every error located
+/// in that subgraph, plus a `supergraph` test case for errors which aren't located in any
+/// subgraph. Hints are included as the output of the test cases they're located in.
+///
+/// A failed [`BuildResult`] has no hints, so use [`PluginResult::to_junit_xml`] to include the
+/// hints of failed builds too.
+pub fn to_junit_xml<S: AsRef<str>>(result: &BuildResult, subgraph_names: &[S]) -> String {
+    match result {
+        Ok(output) => junit_xml(&[], &output.hints, subgraph_names),
+        Err(errors) => junit_xml(&errors.build_errors, &[], subgraph_names),
+    }
+}
+
+impl PluginResult {
+    /// Reports this result as JUnit XML, see [`to_junit_xml`]. Hints are included whether or not
+    /// the build failed.
+    pub fn to_junit_xml<S: AsRef<str>>(&self, subgraph_names: &[S]) -> String {
+        let (errors, hints): (Vec<_>, Vec<_>) = self
+            .build_messages
+            .iter()
+            .cloned()
+            .partition(|message| message.level == BuildMessageLevel::Error);
+        let errors: Vec<BuildError> = errors.into_iter().map(BuildError::from).collect();
+        let hints: Vec<BuildHint> = hints.into_iter().map(BuildHint::from).collect();
+        junit_xml(&errors, &hints, subgraph_names)
+    }
+}
+
+fn junit_xml<S: AsRef<str>>(
+    errors: &[BuildError],
+    hints: &[BuildHint],
+    subgraph_names: &[S],
+) -> String {
+    let mut test_cases: Vec<TestCase> = subgraph_names
+        .iter()
+        .map(|name| TestCase::new(format!("subgraph {}", name.as_ref()), Some(name.as_ref())))
+        .collect();
+    test_cases.push(TestCase::new(SUPERGRAPH_TEST_CASE.to_string(), None));
+
+    for error in errors {
+        let code = error.code.as_deref().unwrap_or("UNKNOWN");
+        for index in test_cases_for(&test_cases, error.nodes.as_deref()) {
+            test_cases[index].codes.insert(code.to_string());
+            test_cases[index].failures.push(error.to_string());
+        }
+    }
+    for hint in hints {
+        let line = match &hint.code {
+            Some(code) => format!("{code}: {}", hint.message),
+            None => hint.message.clone(),
+        };
+        for index in test_cases_for(&test_cases, hint.nodes.as_deref()) {
+            test_cases[index].output.push(line.clone());
+        }
+    }
+
+    let tests = test_cases.len();
+    let failures = test_cases
+        .iter()
+        .filter(|test_case| !test_case.failures.is_empty())
+        .count();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        r#"<testsuites name="{SUITE_NAME}" tests="{tests}" failures="{failures}">"#
+    );
+    let _ = writeln!(
+        xml,
+        r#"  <testsuite name="{SUITE_NAME}" tests="{tests}" failures="{failures}">"#
+    );
+    for test_case in &test_cases {
+        test_case.write(&mut xml);
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+struct TestCase<'a> {
+    name: String,
+    subgraph: Option<&'a str>,
+    codes: BTreeSet<String>,
+    failures: Vec<String>,
+    output: Vec<String>,
+}
+
+impl<'a> TestCase<'a> {
+    fn new(name: String, subgraph: Option<&'a str>) -> Self {
+        Self {
+            name,
+            subgraph,
+            codes: BTreeSet::new(),
+            failures: Vec::new(),
+            output: Vec::new(),
+        }
+    }
+
+    fn write(&self, xml: &mut String) {
+        let _ = write!(
+            xml,
+            r#"    <testcase name="{}" classname="{SUITE_NAME}""#,
+            escape(&self.name)
+        );
+        if self.failures.is_empty() && self.output.is_empty() {
+            xml.push_str(" />\n");
+            return;
+        }
+        xml.push_str(">\n");
+        if let Some(first) = self.failures.first() {
+            let message = match self.failures.len() {
+                1 => first.clone(),
+                len => format!("{len} build errors"),
+            };
+            let codes: Vec<&str> = self.codes.iter().map(String::as_str).collect();
+            let _ = writeln!(
+                xml,
+                r#"      <failure message="{}" type="{}">{}</failure>"#,
+                escape(&message),
+                escape(&codes.join(", ")),
+                escape(&self.failures.join("\n"))
+            );
+        }
+        if !self.output.is_empty() {
+            let _ = writeln!(
+                xml,
+                "      <system-out>{}</system-out>",
+                escape(&self.output.join("\n"))
+            );
+        }
+        xml.push_str("    </testcase>\n");
+    }
+}
+
+/// The indexes of the test cases for each subgraph in `nodes`, or of the supergraph test case
+/// (which is always last) if there are none.
+fn test_cases_for(test_cases: &[TestCase], nodes: Option<&[BuildMessageLocation]>) -> Vec<usize> {
+    let subgraphs: BTreeSet<&str> = nodes
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|node| node.subgraph.as_deref())
+        .collect();
+    let located: Vec<usize> = test_cases
+        .iter()
+        .enumerate()
+        .filter(|(_, test_case)| test_case.subgraph.is_some_and(|s| subgraphs.contains(s)))
+        .map(|(index, _)| index)
+        .collect();
+    if located.is_empty() {
+        vec![test_cases.len() - 1]
+    } else {
+        located
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' | '\t' | '\r' => escaped.push(c),
+            // Other control characters aren't allowed in XML 1.0, even escaped
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build_plugin::{BuildMessage, PluginFailureReason};
+
+    fn message(level: BuildMessageLevel, code: &str, subgraph: Option<&str>) -> BuildMessage {
+        let mut message = BuildMessage::new_error(
+            format!("Something is <wrong> with \"{code}\""),
+            None,
+            Some(code.to_string()),
+        );
+        message.level = level;
+        message.locations = subgraph
+            .map(|subgraph| BuildMessageLocation {
+                subgraph: Some(subgraph.to_string()),
+                ..Default::default()
+            })
+            .into_iter()
+            .collect();
+        message
+    }
+
+    #[test]
+    fn it_reports_failures_per_subgraph() {
+        let result = PluginResult::new_failure(
+            vec![
+                message(BuildMessageLevel::Error, "A", Some("products")),
+                message(BuildMessageLevel::Error, "B", Some("products")),
+                message(BuildMessageLevel::Error, "C", None),
+            ],
+            PluginFailureReason::Build,
+        );
+        assert_eq!(
+            result.to_junit_xml(&["products", "reviews"]),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="composition" tests="3" failures="2">
+  <testsuite name="composition" tests="3" failures="2">
+    <testcase name="subgraph products" classname="composition">
+      <failure message="2 build errors" type="A, B">A: Something is &lt;wrong&gt; with &quot;A&quot;
+B: Something is &lt;wrong&gt; with &quot;B&quot;</failure>
+    </testcase>
+    <testcase name="subgraph reviews" classname="composition" />
+    <testcase name="supergraph" classname="composition">
+      <failure message="C: Something is &lt;wrong&gt; with &quot;C&quot;" type="C">C: Something is &lt;wrong&gt; with &quot;C&quot;</failure>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+
+    #[test]
+    fn it_reports_hints_on_success() {
+        let result = PluginResult::new(
+            Ok("supergraph".to_string()),
+            vec![message(BuildMessageLevel::Warn, "HINT", Some("reviews"))],
+        );
+        assert_eq!(
+            result.to_junit_xml(&["products", "reviews"]),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="composition" tests="3" failures="0">
+  <testsuite name="composition" tests="3" failures="0">
+    <testcase name="subgraph products" classname="composition" />
+    <testcase name="subgraph reviews" classname="composition">
+      <system-out>HINT: Something is &lt;wrong&gt; with &quot;HINT&quot;</system-out>
+    </testcase>
+    <testcase name="supergraph" classname="composition" />
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+
+    #[test]
+    fn it_reports_hints_on_failure() {
+        let result = PluginResult::new_failure(
+            vec![
+                message(BuildMessageLevel::Error, "A", Some("products")),
+                message(BuildMessageLevel::Warn, "HINT", Some("reviews")),
+            ],
+            PluginFailureReason::Build,
+        );
+        assert_eq!(
+            result.to_junit_xml(&["products", "reviews"]),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="composition" tests="3" failures="1">
+  <testsuite name="composition" tests="3" failures="1">
+    <testcase name="subgraph products" classname="composition">
+      <failure message="A: Something is &lt;wrong&gt; with &quot;A&quot;" type="A">A: Something is &lt;wrong&gt; with &quot;A&quot;</failure>
+    </testcase>
+    <testcase name="subgraph reviews" classname="composition">
+      <system-out>HINT: Something is &lt;wrong&gt; with &quot;HINT&quot;</system-out>
+    </testcase>
+    <testcase name="supergraph" classname="composition" />
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+}
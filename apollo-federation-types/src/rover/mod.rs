@@ -2,12 +2,14 @@
 
 pub use error::{BuildError, BuildErrorType, BuildErrors};
 pub use hint::BuildHint;
+pub use junit::to_junit_xml;
 pub use output::BuildOutput;
 
 use crate::build_plugin::{BuildMessageLevel, PluginFailureReason, PluginResult};
 
 mod error;
 mod hint;
+mod junit;
 mod output;
 
 /// The type representing the result of a supergraph build (for any version)
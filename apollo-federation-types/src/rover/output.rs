@@ -45,7 +45,7 @@ mod tests {
     fn it_can_serialize_without_hints() {
         let sdl = "my-sdl".to_string();
         let expected_json = json!({"supergraphSdl": &sdl, "hints": []});
-        let actual_json = serde_json::to_value(&BuildOutput::new(sdl)).unwrap();
+        let actual_json = serde_json::to_value(BuildOutput::new(sdl)).unwrap();
         assert_eq!(expected_json, actual_json)
     }
 